@@ -183,12 +183,64 @@ impl ReceiveSlices<'_> {
     }
 }
 
+impl RxToken<'_> {
+    /// Returns the 1588 timer's nanoseconds when the frame was received.
+    pub(crate) fn timestamp(&self) -> u32 {
+        self.descriptor.timestamp_1588.load(Ordering::Relaxed)
+    }
+}
+
 impl TransmitSlices<'_> {
     pub(crate) fn next_token<'a>(&'a mut self, ready: crate::TxReady<'a>) -> Option<TxToken<'a>> {
         self.next_impl(|txbd| !txbd.is_ready(), ready)
     }
 }
 
+impl TransmitSlices<'_> {
+    /// Returns the 1588 timer's nanoseconds when the frame was sent.
+    ///
+    /// Returns `None` if the DMA still owns the descriptor.
+    pub(crate) fn timestamp(&self, index: usize) -> Option<u32> {
+        let descriptor = self.ring.get(index)?;
+        if descriptor.is_ready()
+            || descriptor.last_bdu.load(Ordering::SeqCst) & txbd::LAST_BDU_UPDATED == 0
+        {
+            return None;
+        }
+        Some(descriptor.timestamp_1588.load(Ordering::Relaxed))
+    }
+}
+
+impl TransmitSlices<'_> {
+    /// Take back all descriptors from the DMA, and restart at the first descriptor.
+    ///
+    /// Frames that the DMA has not sent are dropped. Only call this once the
+    /// DMA is idle.
+    pub(crate) fn rewind(&mut self) {
+        for descriptor in self.ring.iter() {
+            descriptor
+                .flags
+                .fetch_and(txbd::FLAGS_WRAP, Ordering::SeqCst);
+        }
+        self.index = 0;
+    }
+}
+
+impl ReceiveSlices<'_> {
+    /// Give all descriptors to the DMA, and restart at the first descriptor.
+    ///
+    /// Received frames are dropped. Only call this once the DMA is idle.
+    pub(crate) fn rewind(&mut self) {
+        for descriptor in self.ring.iter() {
+            let wrap = descriptor.flags.load(Ordering::Relaxed) & rxbd::FLAGS_WRAP;
+            descriptor
+                .flags
+                .store(rxbd::FLAGS_EMPTY | wrap, Ordering::SeqCst);
+        }
+        self.index = 0;
+    }
+}
+
 impl smoltcp::phy::TxToken for TxToken<'_> {
     fn consume<R, F>(self, len: usize, f: F) -> R
     where
@@ -209,20 +261,36 @@ impl smoltcp::phy::TxToken for TxToken<'_> {
         self.descriptor
             .data_length
             .store(len as _, Ordering::Relaxed);
+        if self.ready.wants_timestamp() {
+            self.descriptor
+                .control
+                .fetch_or(txbd::CONTROL_TIMESTAMP, Ordering::Relaxed);
+            self.descriptor.last_bdu.store(0, Ordering::Relaxed);
+        } else {
+            self.descriptor
+                .control
+                .fetch_and(!txbd::CONTROL_TIMESTAMP, Ordering::Relaxed);
+        }
         self.descriptor.flags.fetch_or(
             txbd::FLAGS_READY | txbd::FLAGS_LAST_IN | txbd::FLAGS_TRANSMIT_CRC,
             Ordering::SeqCst,
         );
-        self.ready.consume();
+        self.ready.consume(*self.index);
         *self.index = self.next;
         result
     }
+
+    #[cfg(feature = "packetmeta-id")]
+    fn set_meta(&mut self, meta: smoltcp::phy::PacketMeta) {
+        self.ready.set_id(meta.id);
+    }
 }
 
-impl smoltcp::phy::RxToken for RxToken<'_> {
-    fn consume<R, F>(self, f: F) -> R
+impl RxToken<'_> {
+    /// Consume the received frame, then release the descriptor to the DMA.
+    pub(crate) fn consume_mut<R, F>(self, f: F) -> R
     where
-        F: FnOnce(&[u8]) -> R,
+        F: FnOnce(&mut [u8]) -> R,
     {
         // Safety: hardware will not exceed our maximum frame length. We know that
         // the pointer is valid; see discussion above.
@@ -242,3 +310,67 @@ impl smoltcp::phy::RxToken for RxToken<'_> {
         result
     }
 }
+
+impl smoltcp::phy::RxToken for RxToken<'_> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.consume_mut(|buffer| f(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReceiveBuffers, TransmitBuffers, rxbd, txbd};
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn rewind_transmit() {
+        let buffers = Box::leak(Box::new(TransmitBuffers::<3, 64>::new()));
+        let mut slices = buffers.take();
+        for descriptor in slices.ring.iter() {
+            descriptor
+                .flags
+                .fetch_or(txbd::FLAGS_READY | txbd::FLAGS_LAST_IN, Ordering::Relaxed);
+        }
+        slices.index = 2;
+
+        slices.rewind();
+        assert_eq!(slices.index, 0);
+        let flags: Vec<u16> = slices
+            .ring
+            .iter()
+            .map(|descriptor| descriptor.flags.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(flags, [0, 0, txbd::FLAGS_WRAP]);
+    }
+
+    #[test]
+    fn rewind_receive() {
+        let buffers = Box::leak(Box::new(ReceiveBuffers::<3, 64>::new()));
+        let mut slices = buffers.take();
+        for descriptor in slices.ring.iter() {
+            descriptor
+                .flags
+                .fetch_and(!rxbd::FLAGS_EMPTY, Ordering::Relaxed);
+        }
+        slices.index = 1;
+
+        slices.rewind();
+        assert_eq!(slices.index, 0);
+        let flags: Vec<u16> = slices
+            .ring
+            .iter()
+            .map(|descriptor| descriptor.flags.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                rxbd::FLAGS_EMPTY,
+                rxbd::FLAGS_EMPTY,
+                rxbd::FLAGS_EMPTY | rxbd::FLAGS_WRAP
+            ]
+        );
+    }
+}
@@ -21,6 +21,10 @@ pub const FLAGS_WRAP: u16 = 1 << 13;
 pub const FLAGS_LAST_IN: u16 = 1 << 11;
 pub const FLAGS_TRANSMIT_CRC: u16 = 1 << 10;
 
+pub const CONTROL_TIMESTAMP: u16 = 1 << 13;
+
+pub const LAST_BDU_UPDATED: u16 = 1 << 15;
+
 impl TxBD {
     pub(crate) const fn zero() -> Self {
         Self {
@@ -0,0 +1,355 @@
+//! Driver configuration.
+
+use crate::{Duplex, LinkError, MiiMode, Speed, link};
+
+/// The default MDIO clock frequency, which every PHY supports.
+pub(crate) const MDC_FREQUENCY_HZ: u32 = 2_500_000;
+/// The default MDIO output hold time.
+pub(crate) const MDIO_HOLD_TIME_NS: u32 = 10;
+
+/// The largest `MAX_FL` value.
+const MAX_FRAME_LENGTH: u16 = (1 << 14) - 1;
+/// The smallest Ethernet frame, including the CRC.
+const MIN_FRAME_LENGTH: u16 = 64;
+
+/// Configurations for an [`Enet`](crate::Enet) driver.
+///
+/// Use [`Enet::try_new`](crate::Enet::try_new) to create a driver with
+/// this configuration. The driver checks the configuration before it
+/// touches the peripheral.
+///
+/// ```
+/// use imxrt_enet::{Duplex, EnetConfig, MiiMode, Speed};
+///
+/// let config = EnetConfig::new(50_000_000, [6, 5, 4, 3, 2, 1])
+///     .link(MiiMode::Rmii, Speed::M100, Duplex::Full)
+///     .broadcast_reject(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EnetConfig {
+    pub(crate) source_clock_hz: u32,
+    pub(crate) mac: [u8; 6],
+    pub(crate) mode: MiiMode,
+    pub(crate) speed: Speed,
+    pub(crate) duplex: Duplex,
+    pub(crate) max_frame_length: u16,
+    pub(crate) promiscuous: bool,
+    pub(crate) broadcast_reject: bool,
+    pub(crate) accept_all_multicast: bool,
+    pub(crate) checksum: ChecksumOffload,
+    pub(crate) mdc_hz: u32,
+    pub(crate) mdio_hold_time_ns: u32,
+    pub(crate) mdio_preamble: bool,
+}
+
+impl EnetConfig {
+    /// Create a configuration for an ENET clocked at `source_clock_hz`,
+    /// with the MAC address `mac`.
+    ///
+    /// By default, the configuration
+    ///
+    /// - uses MII at 100Mbit/s, half duplex.
+    /// - accepts frames up to 1518 bytes, which excludes VLAN tags.
+    /// - accepts broadcast frames, but rejects multicast frames.
+    /// - offloads IP and protocol checksums to the hardware.
+    /// - uses an MDIO clock of at most 2.5MHz, a hold time of at least 10ns,
+    ///   and an MDIO preamble.
+    pub const fn new(source_clock_hz: u32, mac: [u8; 6]) -> Self {
+        Self {
+            source_clock_hz,
+            mac,
+            mode: MiiMode::Mii,
+            speed: Speed::M100,
+            duplex: Duplex::Half,
+            max_frame_length: 1518,
+            promiscuous: false,
+            broadcast_reject: false,
+            accept_all_multicast: false,
+            checksum: ChecksumOffload { rx: true, tx: true },
+            mdc_hz: MDC_FREQUENCY_HZ,
+            mdio_hold_time_ns: MDIO_HOLD_TIME_NS,
+            mdio_preamble: true,
+        }
+    }
+
+    /// Configure the link.
+    ///
+    /// See [`Enet::set_link`](crate::Enet::set_link) for more information.
+    pub const fn link(mut self, mode: MiiMode, speed: Speed, duplex: Duplex) -> Self {
+        self.mode = mode;
+        self.speed = speed;
+        self.duplex = duplex;
+        self
+    }
+
+    /// Set the maximum length of a received frame, in bytes.
+    ///
+    /// The MAC truncates longer frames. The length includes the CRC, and
+    /// it cannot exceed the size of a receive buffer.
+    pub const fn max_frame_length(mut self, max_frame_length: u16) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Enable (`true`) or disable (`false`) promiscuous mode.
+    ///
+    /// See [`Enet::enable_promiscuous_mode`](crate::Enet::enable_promiscuous_mode)
+    /// for more information.
+    pub const fn promiscuous(mut self, enable: bool) -> Self {
+        self.promiscuous = enable;
+        self
+    }
+
+    /// Enable (`true`) or disable (`false`) broadcast frame rejection.
+    ///
+    /// See [`Enet::enable_broadcast_reject`](crate::Enet::enable_broadcast_reject)
+    /// for more information.
+    pub const fn broadcast_reject(mut self, enable: bool) -> Self {
+        self.broadcast_reject = enable;
+        self
+    }
+
+    /// Accept (`true`) all multicast frames, or only accept frames for
+    /// joined multicast groups (`false`).
+    ///
+    /// See [`Enet::accept_all_multicast`](crate::Enet::accept_all_multicast)
+    /// for more information.
+    pub const fn accept_all_multicast(mut self, accept: bool) -> Self {
+        self.accept_all_multicast = accept;
+        self
+    }
+
+    /// Check (`true`) the IP and protocol checksums of received frames in
+    /// hardware, or leave it to smoltcp (`false`).
+    ///
+    /// When enabled, the MAC discards frames with bad checksums.
+    pub const fn rx_checksum_offload(mut self, enable: bool) -> Self {
+        self.checksum.rx = enable;
+        self
+    }
+
+    /// Insert (`true`) the IP and protocol checksums of transmitted frames
+    /// in hardware, or leave it to smoltcp (`false`).
+    pub const fn tx_checksum_offload(mut self, enable: bool) -> Self {
+        self.checksum.tx = enable;
+        self
+    }
+
+    /// Set the maximum MDIO clock (MDC) frequency.
+    ///
+    /// See [`Mdio::set_mdc_frequency`](crate::Mdio::set_mdc_frequency) for
+    /// more information.
+    pub const fn mdc_frequency(mut self, mdc_hz: u32) -> Self {
+        self.mdc_hz = mdc_hz;
+        self
+    }
+
+    /// Set the minimum MDIO output hold time, in nanoseconds.
+    ///
+    /// See [`Mdio::set_hold_time`](crate::Mdio::set_hold_time) for
+    /// more information.
+    pub const fn mdio_hold_time(mut self, hold_time_ns: u32) -> Self {
+        self.mdio_hold_time_ns = hold_time_ns;
+        self
+    }
+
+    /// Send (`true`) or suppress (`false`) the MDIO preamble.
+    ///
+    /// See [`Mdio::enable_preamble`](crate::Mdio::enable_preamble)
+    /// for more information.
+    pub const fn mdio_preamble(mut self, enable: bool) -> Self {
+        self.mdio_preamble = enable;
+        self
+    }
+
+    /// Check the configuration for a receive ring with `rx_mtu`-sized buffers.
+    ///
+    /// `gigabit` indicates if the ENET instance supports RGMII and gigabit speeds.
+    pub(crate) fn validate(&self, rx_mtu: usize, gigabit: bool) -> Result<Mscr, ConfigError> {
+        let mscr = Mscr {
+            mii_speed: mii_speed(self.source_clock_hz, self.mdc_hz)?,
+            hold_time: hold_time(self.source_clock_hz, self.mdio_hold_time_ns)?,
+            preamble: self.mdio_preamble,
+        };
+
+        if self.max_frame_length < MIN_FRAME_LENGTH
+            || self.max_frame_length > MAX_FRAME_LENGTH
+            || self.max_frame_length as usize > rx_mtu
+        {
+            return Err(ConfigError::MaxFrameLength(self.max_frame_length));
+        }
+
+        link::validate(gigabit, self.mode, self.speed, self.duplex)?;
+        Ok(mscr)
+    }
+}
+
+/// Checksums computed by the hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct ChecksumOffload {
+    pub(crate) rx: bool,
+    pub(crate) tx: bool,
+}
+
+/// An invalid driver configuration.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// The source clock frequency is zero, or the instance cannot use it.
+    SourceClock(u32),
+    /// The source clock cannot be divided to the MDIO clock frequency.
+    MdcFrequency(u32),
+    /// The source clock cannot produce the MDIO hold time, in nanoseconds.
+    HoldTime(u32),
+    /// The maximum frame length is shorter than an Ethernet frame, or it's
+    /// larger than a receive buffer.
+    MaxFrameLength(u16),
+    /// The instance cannot support the link.
+    Link(LinkError),
+    /// The instance did not finish its reset in time. Check that its
+    /// clocks are running.
+    #[cfg(feature = "enet-qos")]
+    ResetTimeout,
+}
+
+impl From<LinkError> for ConfigError {
+    fn from(err: LinkError) -> Self {
+        Self::Link(err)
+    }
+}
+
+/// MII speed control register fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Mscr {
+    pub(crate) mii_speed: u32,
+    pub(crate) hold_time: u32,
+    pub(crate) preamble: bool,
+}
+
+/// Returns the `MII_SPEED` for an MDIO clock no faster than `mdc_hz`.
+pub(crate) fn mii_speed(source_clock_hz: u32, mdc_hz: u32) -> Result<u32, ConfigError> {
+    if source_clock_hz == 0 {
+        return Err(ConfigError::SourceClock(source_clock_hz));
+    }
+    if mdc_hz == 0 {
+        return Err(ConfigError::MdcFrequency(mdc_hz));
+    }
+
+    // MDC = source / ((MII_SPEED + 1) * 2). Zero turns off MDC.
+    let mii_speed = (source_clock_hz as u64).div_ceil(2 * mdc_hz as u64) - 1;
+    if !(1..=0x3F).contains(&mii_speed) {
+        return Err(ConfigError::MdcFrequency(mdc_hz));
+    }
+    Ok(mii_speed as u32)
+}
+
+/// Returns the MDIO clock frequency for a `MII_SPEED`.
+pub(crate) const fn mdc_frequency(source_clock_hz: u32, mii_speed: u32) -> u32 {
+    source_clock_hz / ((mii_speed + 1) * 2)
+}
+
+/// Returns the `HOLDTIME` for a hold time no shorter than `hold_time_ns`.
+pub(crate) fn hold_time(source_clock_hz: u32, hold_time_ns: u32) -> Result<u32, ConfigError> {
+    if source_clock_hz == 0 {
+        return Err(ConfigError::SourceClock(source_clock_hz));
+    }
+
+    // The hold time is HOLDTIME + 1 source clock cycles.
+    let cycles = (hold_time_ns as u64 * source_clock_hz as u64).div_ceil(1_000_000_000);
+    let hold_time = cycles.max(1) - 1;
+    if hold_time > 0x7 {
+        return Err(ConfigError::HoldTime(hold_time_ns));
+    }
+    Ok(hold_time as u32)
+}
+
+/// Returns the hold time for a `HOLDTIME`, rounded up to the next nanosecond.
+pub(crate) const fn hold_time_ns(source_clock_hz: u32, hold_time: u32) -> u32 {
+    ((hold_time as u64 + 1) * 1_000_000_000).div_ceil(source_clock_hz as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigError, EnetConfig, hold_time, hold_time_ns, mdc_frequency, mii_speed};
+    use crate::{Duplex, LinkError, MiiMode, Speed};
+
+    #[test]
+    fn mdc() {
+        assert_eq!(mii_speed(50_000_000, 2_500_000), Ok(9));
+        assert_eq!(mdc_frequency(50_000_000, 9), 2_500_000);
+        assert_eq!(mii_speed(132_000_000, 2_500_000), Ok(26));
+        assert_eq!(mdc_frequency(132_000_000, 26), 2_444_444);
+        assert_eq!(mii_speed(320_000_000, 2_500_000), Ok(63));
+        assert_eq!(mii_speed(50_000_000, 12_500_000), Ok(1));
+
+        // Too fast for the divider, or too slow to be divided.
+        assert_eq!(
+            mii_speed(2_000_000_000, 2_500_000),
+            Err(ConfigError::MdcFrequency(2_500_000))
+        );
+        assert_eq!(
+            mii_speed(5_000_000, 2_500_000),
+            Err(ConfigError::MdcFrequency(2_500_000))
+        );
+        assert_eq!(mii_speed(50_000_000, 0), Err(ConfigError::MdcFrequency(0)));
+        assert_eq!(mii_speed(0, 2_500_000), Err(ConfigError::SourceClock(0)));
+    }
+
+    #[test]
+    fn hold() {
+        assert_eq!(hold_time(50_000_000, 10), Ok(0));
+        assert_eq!(hold_time_ns(50_000_000, 0), 20);
+        assert_eq!(hold_time(132_000_000, 10), Ok(1));
+        assert_eq!(hold_time_ns(132_000_000, 1), 16);
+        assert_eq!(hold_time(320_000_000, 10), Ok(3));
+        assert_eq!(hold_time(50_000_000, 160), Ok(7));
+        assert_eq!(hold_time(50_000_000, 200), Err(ConfigError::HoldTime(200)));
+        assert_eq!(hold_time(0, 10), Err(ConfigError::SourceClock(0)));
+    }
+
+    #[test]
+    fn validate() {
+        let config = EnetConfig::new(50_000_000, [0; 6]);
+        assert!(config.validate(1536, false).is_ok());
+
+        assert_eq!(
+            EnetConfig::new(0, [0; 6]).validate(1536, false),
+            Err(ConfigError::SourceClock(0))
+        );
+        assert_eq!(
+            config.mdc_frequency(30_000_000).validate(1536, false),
+            Err(ConfigError::MdcFrequency(30_000_000))
+        );
+        assert_eq!(
+            config.max_frame_length(1522).validate(1520, false),
+            Err(ConfigError::MaxFrameLength(1522))
+        );
+        assert_eq!(
+            config.max_frame_length(63).validate(1536, false),
+            Err(ConfigError::MaxFrameLength(63))
+        );
+        assert_eq!(
+            config
+                .max_frame_length(u16::MAX)
+                .validate(usize::MAX, false),
+            Err(ConfigError::MaxFrameLength(u16::MAX))
+        );
+        assert_eq!(
+            config
+                .link(MiiMode::Rgmii, Speed::M1000, Duplex::Full)
+                .validate(1536, false),
+            Err(ConfigError::Link(LinkError::UnsupportedMode(
+                MiiMode::Rgmii
+            )))
+        );
+        assert!(
+            config
+                .link(MiiMode::Rgmii, Speed::M1000, Duplex::Full)
+                .validate(1536, true)
+                .is_ok()
+        );
+    }
+}
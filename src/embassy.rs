@@ -0,0 +1,268 @@
+//! An [`embassy-net`](https://docs.rs/embassy-net) driver.
+//!
+//! Allocate a static [`State`], then use [`new`] to split your [`Enet`]
+//! driver into a [`Device`] and an [`InterruptHandler`]. Give the device
+//! to `embassy-net`, and call the interrupt handler from your ENET
+//! interrupt.
+//!
+//! ```rust,no_run
+//! use imxrt_enet::{Enet, Running, embassy::{self, State}};
+//!
+//! static STATE: State = State::new();
+//!
+//! # fn get_enet() -> Enet<Running> { unimplemented!() }
+//! let enet: Enet<Running> = // Your configured and enabled driver.
+//! # get_enet();
+//! let (device, interrupt) = embassy::new(enet, &STATE);
+//!
+//! // Call this from your ENET interrupt handler.
+//! interrupt.on_interrupt();
+//!
+//! // Once your PHY reports a link...
+//! STATE.set_link_state(embassy_net_driver::LinkState::Up);
+//! ```
+//!
+//! The ENET peripheral does not observe the state of the link; your PHY
+//! does. The device reports that the link is down until you use
+//! [`State::set_link_state`].
+//!
+//! The interrupt handler counts the seconds of the
+//! [IEEE 1588 timer](crate::Enet::ptp_clock), so you don't need to read the
+//! time once per second.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::task::Context;
+
+use atomic_waker::AtomicWaker;
+use embassy_net_driver as driver;
+
+use crate::{Enet, Interrupts, Running, ral};
+
+/// Shared state between a [`Device`] and its [`InterruptHandler`].
+///
+/// Allocate this in a static.
+pub struct State {
+    rx: AtomicWaker,
+    tx: AtomicWaker,
+    link: AtomicWaker,
+    link_up: AtomicBool,
+    /// Seconds of the 1588 timer, counted by the interrupt handler.
+    seconds: AtomicU32,
+}
+
+impl State {
+    /// Create the state.
+    ///
+    /// The link is down.
+    pub const fn new() -> Self {
+        Self {
+            rx: AtomicWaker::new(),
+            tx: AtomicWaker::new(),
+            link: AtomicWaker::new(),
+            link_up: AtomicBool::new(false),
+            seconds: AtomicU32::new(0),
+        }
+    }
+
+    /// Set the link state reported to `embassy-net`.
+    ///
+    /// Call this when your PHY reports a link change. This wakes the
+    /// network stack if the state changes.
+    pub fn set_link_state(&self, state: driver::LinkState) {
+        let up = matches!(state, driver::LinkState::Up);
+        if self.link_up.swap(up, Ordering::AcqRel) != up {
+            self.link.wake();
+        }
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split the driver into an `embassy-net` device and its interrupt handler.
+///
+/// This enables the receive and transmit frame interrupts, including those
+/// of the other ENET_1G queues, and the 1588 timer interrupt. Other enabled
+/// interrupt sources remain enabled. Keep the 1588 timer interrupt enabled
+/// while you use the device.
+pub fn new(
+    mut enet: Enet<Running>,
+    state: &'static State,
+) -> (Device<'static>, InterruptHandler<'static>) {
+    if enet.gigabit {
+        enet.enable_interrupts(Interrupts::RX_FRAMES | Interrupts::TX_FRAMES);
+    } else {
+        enet.enable_interrupts(Interrupts::RXF | Interrupts::TXF);
+    }
+    enet.enable_interrupts(Interrupts::TS_TIMER);
+    enet.ptp.count_seconds_in(Some(&state.seconds));
+    let rb: *const ral::enet::RegisterBlock = &*enet.enet;
+    (Device { enet, state }, InterruptHandler { enet: rb, state })
+}
+
+/// Wakes the [`Device`] from the ENET interrupt.
+pub struct InterruptHandler<'a> {
+    enet: *const ral::enet::RegisterBlock,
+    state: &'a State,
+}
+
+// Safety: the handler points to static MMIO. It only reads EIMR,
+// and it only clears EIR flags by writing ones. These accesses do
+// not conflict with the device's accesses.
+unsafe impl Send for InterruptHandler<'_> {}
+
+impl InterruptHandler<'_> {
+    /// Handle the ENET interrupt.
+    ///
+    /// Returns the events that triggered the interrupt, and clears those
+    /// events. Only events from enabled interrupt sources are returned and
+    /// cleared.
+    ///
+    /// If [`Interrupts::TS_TIMER`] is returned, the handler counts a new
+    /// second for the 1588 timer.
+    pub fn on_interrupt(&self) -> Interrupts {
+        // Safety: pointer is to static MMIO.
+        let enet = unsafe { &*self.enet };
+        let enabled = ral::read_reg!(ral::enet, enet, EIMR);
+        let events = Interrupts::from_bits_truncate(ral::read_reg!(ral::enet, enet, EIR) & enabled);
+        ral::write_reg!(ral::enet, enet, EIR, events.bits());
+
+        if events.intersects(Interrupts::TS_TIMER) {
+            self.state.seconds.fetch_add(1, Ordering::AcqRel);
+        }
+        if events.intersects(Interrupts::RX_FRAMES) {
+            self.state.rx.wake();
+        }
+        if events.intersects(Interrupts::TX_FRAMES) {
+            self.state.tx.wake();
+        }
+        events
+    }
+}
+
+/// An `embassy-net` device.
+///
+/// Use [`enet`](Device::enet) and [`enet_mut`](Device::enet_mut) to access
+/// the driver.
+pub struct Device<'a> {
+    enet: Enet<Running>,
+    state: &'a State,
+}
+
+impl Device<'_> {
+    /// Access the driver.
+    pub fn enet(&self) -> &Enet<Running> {
+        &self.enet
+    }
+
+    /// Access the driver.
+    ///
+    /// Don't call [`Enet::on_interrupt`] on the driver. The
+    /// [`InterruptHandler`] handles the ENET interrupt, and it counts the
+    /// 1588 timer's seconds. If both handle the interrupt, they steal each
+    /// other's events, and the timer counts some seconds twice.
+    pub fn enet_mut(&mut self) -> &mut Enet<Running> {
+        &mut self.enet
+    }
+
+    /// Release the driver.
+    ///
+    /// Interrupt sources remain enabled. The driver counts the 1588 timer's
+    /// seconds when you read the time, or when you call
+    /// [`Enet::on_interrupt`].
+    pub fn release(mut self) -> Enet<Running> {
+        self.enet.ptp.count_seconds_in(None);
+        self.enet
+    }
+}
+
+/// A received frame.
+#[doc(hidden)]
+pub struct RxToken<'a>(crate::bd::RxToken<'a>);
+
+impl driver::RxToken for RxToken<'_> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        self.0.consume_mut(f)
+    }
+}
+
+/// A frame to transmit.
+#[doc(hidden)]
+pub struct TxToken<'a>(crate::bd::TxToken<'a>);
+
+impl driver::TxToken for TxToken<'_> {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        smoltcp::phy::TxToken::consume(self.0, len, f)
+    }
+}
+
+impl driver::Driver for Device<'_> {
+    type RxToken<'a>
+        = RxToken<'a>
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken<'a>
+    where
+        Self: 'a;
+
+    fn receive(&mut self, cx: &mut Context) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        self.state.rx.register(cx.waker());
+        self.state.tx.register(cx.waker());
+
+        let (rx, tx) = self.enet.next_tokens()?;
+        Some((RxToken(rx), TxToken(tx)))
+    }
+
+    fn transmit(&mut self, cx: &mut Context) -> Option<Self::TxToken<'_>> {
+        self.state.tx.register(cx.waker());
+
+        self.enet.next_transmit().map(TxToken)
+    }
+
+    fn link_state(&mut self, cx: &mut Context) -> driver::LinkState {
+        self.state.link.register(cx.waker());
+        if self.state.link_up.load(Ordering::Acquire) {
+            driver::LinkState::Up
+        } else {
+            driver::LinkState::Down
+        }
+    }
+
+    fn capabilities(&self) -> driver::Capabilities {
+        let smoltcp = smoltcp::phy::Device::capabilities(&self.enet);
+
+        let mut caps = driver::Capabilities::default();
+        caps.max_transmission_unit = smoltcp.max_transmission_unit;
+        caps.max_burst_size = smoltcp.max_burst_size;
+
+        caps.checksum.ipv4 = checksum(smoltcp.checksum.ipv4);
+        caps.checksum.udp = checksum(smoltcp.checksum.udp);
+        caps.checksum.tcp = checksum(smoltcp.checksum.tcp);
+        caps.checksum.icmpv4 = checksum(smoltcp.checksum.icmpv4);
+
+        caps
+    }
+
+    fn hardware_address(&self) -> driver::HardwareAddress {
+        driver::HardwareAddress::Ethernet(self.enet.mac_address())
+    }
+}
+
+fn checksum(checksum: smoltcp::phy::Checksum) -> driver::Checksum {
+    match checksum {
+        smoltcp::phy::Checksum::Both => driver::Checksum::Both,
+        smoltcp::phy::Checksum::Rx => driver::Checksum::Rx,
+        smoltcp::phy::Checksum::Tx => driver::Checksum::Tx,
+        smoltcp::phy::Checksum::None => driver::Checksum::None,
+    }
+}
@@ -0,0 +1,116 @@
+//! Hash-based address filtering.
+//!
+//! The MAC hashes a frame's destination address, then uses six bits of the
+//! hash to select one of 64 buckets. If the bucket is set, the MAC accepts
+//! the frame. Different addresses can share a bucket, so the filter counts
+//! the addresses in each bucket.
+
+/// Compute the Ethernet CRC-32 of an address, without the final inversion.
+const fn crc32(address: &[u8; 6]) -> u32 {
+    const POLYNOMIAL: u32 = 0xEDB8_8320;
+    let mut crc = u32::MAX;
+    let mut idx = 0;
+    while idx < address.len() {
+        let mut byte = address[idx];
+        let mut bit = 0;
+        while bit < 8 {
+            let feedback = (crc ^ byte as u32) & 1;
+            crc >>= 1;
+            if feedback != 0 {
+                crc ^= POLYNOMIAL;
+            }
+            byte >>= 1;
+            bit += 1;
+        }
+        idx += 1;
+    }
+    crc
+}
+
+/// Returns the hash bucket for an address.
+const fn bucket(address: &[u8; 6]) -> usize {
+    (crc32(address) >> 26) as usize
+}
+
+/// Counts the addresses in each hash bucket.
+pub(crate) struct HashFilter {
+    counts: [u16; 64],
+}
+
+impl HashFilter {
+    pub(crate) const fn new() -> Self {
+        Self { counts: [0; 64] }
+    }
+
+    /// Add an address to the filter.
+    pub(crate) fn insert(&mut self, address: &[u8; 6]) {
+        let count = &mut self.counts[bucket(address)];
+        *count = count.saturating_add(1);
+    }
+
+    /// Remove an address from the filter.
+    ///
+    /// Returns `false` if the address's bucket was already empty.
+    pub(crate) fn remove(&mut self, address: &[u8; 6]) -> bool {
+        let count = &mut self.counts[bucket(address)];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Returns the (upper, lower) register values for the filter.
+    pub(crate) fn registers(&self) -> (u32, u32) {
+        let mut upper = 0;
+        let mut lower = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+            if bucket < 32 {
+                lower |= 1 << bucket;
+            } else {
+                upper |= 1 << (bucket - 32);
+            }
+        }
+        (upper, lower)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HashFilter, bucket, crc32};
+
+    const MDNS: [u8; 6] = [0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB];
+    const PTP: [u8; 6] = [0x01, 0x1B, 0x19, 0x00, 0x00, 0x00];
+    const BROADCAST: [u8; 6] = [0xFF; 6];
+
+    #[test]
+    fn hash() {
+        assert_eq!(crc32(&MDNS), 0x84DC_DEFC);
+        assert_eq!(bucket(&MDNS), 33);
+        assert_eq!(bucket(&PTP), 47);
+        assert_eq!(bucket(&[0x33, 0x33, 0x00, 0x00, 0x00, 0x01]), 23);
+    }
+
+    #[test]
+    fn shared_buckets_are_counted() {
+        // PTP and broadcast share bucket 47.
+        assert_eq!(bucket(&PTP), bucket(&BROADCAST));
+
+        let mut filter = HashFilter::new();
+        filter.insert(&MDNS);
+        filter.insert(&PTP);
+        filter.insert(&BROADCAST);
+        assert_eq!(filter.registers(), (1 << 1 | 1 << 15, 0));
+
+        assert!(filter.remove(&PTP));
+        assert_eq!(filter.registers(), (1 << 1 | 1 << 15, 0));
+        assert!(filter.remove(&BROADCAST));
+        assert_eq!(filter.registers(), (1 << 1, 0));
+        assert!(!filter.remove(&BROADCAST));
+        assert!(filter.remove(&MDNS));
+        assert_eq!(filter.registers(), (0, 0));
+    }
+}
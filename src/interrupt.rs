@@ -0,0 +1,150 @@
+//! ENET interrupt sources and events.
+
+use crate::ral;
+
+/// A set of ENET interrupt sources or events.
+///
+/// Use this to select the interrupt sources you enable with
+/// [`Enet::enable_interrupts`](crate::Enet::enable_interrupts). It's also
+/// the set of events returned from [`Enet::on_interrupt`](crate::Enet::on_interrupt).
+///
+/// Combine sets with `|`, and intersect sets with `&`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Interrupts(u32);
+
+impl Interrupts {
+    /// The MAC received a frame.
+    pub const RXF: Self = Self(ral::enet::EIR::RXF::mask);
+    /// The MAC sent a frame.
+    pub const TXF: Self = Self(ral::enet::EIR::TXF::mask);
+    /// The DMA encountered a bus error.
+    ///
+    /// When this happens, the MAC stops, and you'll need to reinitialize
+    /// the driver.
+    pub const EBERR: Self = Self(ral::enet::EIR::EBERR::mask);
+    /// The MAC received a frame longer than the maximum frame length.
+    pub const BABR: Self = Self(ral::enet::EIR::BABR::mask);
+    /// The MAC sent a frame longer than the maximum frame length.
+    pub const BABT: Self = Self(ral::enet::EIR::BABT::mask);
+    /// The transmit FIFO underran while sending a frame.
+    pub const UN: Self = Self(ral::enet::EIR::UN::mask);
+    /// A collision happened after the collision window.
+    ///
+    /// Only applies in half-duplex operation.
+    pub const LC: Self = Self(ral::enet::EIR::LC::mask);
+    /// The MAC dropped a frame after too many collisions.
+    ///
+    /// Only applies in half-duplex operation.
+    pub const RL: Self = Self(ral::enet::EIR::RL::mask);
+    /// The MAC received a frame with a payload length error.
+    pub const PLR: Self = Self(ral::enet::EIR::PLR::mask);
+    /// The MAC sent a frame that has a transmit timestamp.
+    pub const TS_AVAIL: Self = Self(ral::enet::EIR::TS_AVAIL::mask);
+    /// The IEEE 1588 timer reached a new second.
+    pub const TS_TIMER: Self = Self(ral::enet::EIR::TS_TIMER::mask);
+    /// The MAC detected a magic packet while sleeping.
+    pub const WAKEUP: Self = Self(ral::enet::EIR::WAKEUP::mask);
+    /// The MAC received a frame in queue 1.
+    #[cfg(feature = "enet-1g")]
+    pub const RXF1: Self = Self(ral::enet_1g::EIR::RXF1::mask);
+    /// The MAC sent a frame from queue 1.
+    #[cfg(feature = "enet-1g")]
+    pub const TXF1: Self = Self(ral::enet_1g::EIR::TXF1::mask);
+    /// The MAC received a frame in queue 2.
+    #[cfg(feature = "enet-1g")]
+    pub const RXF2: Self = Self(ral::enet_1g::EIR::RXF2::mask);
+    /// The MAC sent a frame from queue 2.
+    #[cfg(feature = "enet-1g")]
+    pub const TXF2: Self = Self(ral::enet_1g::EIR::TXF2::mask);
+
+    /// Frames received in any queue.
+    #[cfg(feature = "enet-1g")]
+    pub(crate) const RX_FRAMES: Self = Self(Self::RXF.0 | Self::RXF1.0 | Self::RXF2.0);
+    #[cfg(not(feature = "enet-1g"))]
+    pub(crate) const RX_FRAMES: Self = Self::RXF;
+    /// Frames sent from any queue.
+    #[cfg(feature = "enet-1g")]
+    pub(crate) const TX_FRAMES: Self = Self(Self::TXF.0 | Self::TXF1.0 | Self::TXF2.0);
+    #[cfg(not(feature = "enet-1g"))]
+    pub(crate) const TX_FRAMES: Self = Self::TXF;
+
+    const ALL: u32 = Self::RXF.0
+        | Self::TXF.0
+        | Self::EBERR.0
+        | Self::BABR.0
+        | Self::BABT.0
+        | Self::UN.0
+        | Self::LC.0
+        | Self::RL.0
+        | Self::PLR.0
+        | Self::TS_AVAIL.0
+        | Self::TS_TIMER.0
+        | Self::WAKEUP.0
+        | Self::RX_FRAMES.0
+        | Self::TX_FRAMES.0;
+
+    /// Returns an empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of all supported interrupt sources.
+    pub const fn all() -> Self {
+        Self(Self::ALL)
+    }
+
+    /// Returns the set from `EIR` / `EIMR` register bits.
+    ///
+    /// Unsupported bits are discarded.
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::ALL)
+    }
+
+    /// Returns the `EIR` / `EIMR` register bits for this set.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Indicates if the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Indicates if this set contains every member of `other`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Indicates if this set shares any member with `other`.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl core::ops::BitOr for Interrupts {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for Interrupts {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl core::ops::BitAnd for Interrupts {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::Not for Interrupts {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0 & Self::ALL)
+    }
+}
@@ -20,7 +20,8 @@
 //! - configures a PHY.
 //!
 //! You'll likely need to do these yourself. Although it does not configure the
-//! PHY, the [`Enet`] object has an MDIO interface that you may use.
+//! PHY, the [`Enet`] object has an MDIO interface that you may use. The [`phy`]
+//! module can help you manage your PHY through that interface.
 //!
 //! Allocate [`ReceiveBuffers`] and [`TransmitBuffers`]. These describe
 //!
@@ -29,13 +30,22 @@
 //!   by default)
 //!
 //! Construct and configure your [`Enet`] driver, enable it, and use it with smoltcp.
+//! To validate your configuration while you construct the driver, use an
+//! [`EnetConfig`] with [`Enet::try_new`].
+//!
+//! The i.MX RT 1170's ENET_1G supports RGMII and gigabit speeds. To use it,
+//! enable this package's `enet-1g` feature, then construct the driver with
+//! `Enet::new_1g` or `Enet::try_new_1g`. ENET_1G also has two more transmit
+//! and receive queues; see `Enet::attach_queue`. The i.MX RT 1170's ENET_QOS
+//! is a different MAC; enable the `enet-qos` feature, then see the `qos`
+//! module.
 //!
 //! ```rust,no_run
 //! use static_cell::ConstStaticCell;
-//! use imxrt_enet::{Enet, ReceiveBuffers, TransmitBuffers};
+//! use imxrt_enet::{Configuring, Enet, ReceiveBuffers, TransmitBuffers};
 //! use smoltcp::{iface::{Config, Interface}, wire::EthernetAddress};
 //!
-//! # fn init_phy(enet: &mut Enet) -> Option<()> { Some(()) }
+//! # fn init_phy(enet: &mut Enet<Configuring>) -> Option<()> { Some(()) }
 //! static RX_BUFFERS: ConstStaticCell<ReceiveBuffers<4>> =
 //!     ConstStaticCell::new(ReceiveBuffers::new());
 //! static TX_BUFFERS: ConstStaticCell<TransmitBuffers<4>> =
@@ -67,7 +77,7 @@
 //! // MAC statistics...
 //!
 //! // Eventually, enable the MAC.
-//! enet.enable_mac(true);
+//! let mut enet = enet.enable_mac();
 //!
 //! let mut iface = Interface::new(
 //!    Config::new(EthernetAddress(MAC).into()),
@@ -88,15 +98,45 @@
 )]
 
 mod bd;
+mod config;
+#[cfg(feature = "embassy-net-driver")]
+pub mod embassy;
+mod filter;
+mod interrupt;
+mod link;
+mod mii;
+pub mod mmd;
+mod monitor;
+pub mod phy;
+mod ptp;
+#[cfg(feature = "enet-qos")]
+pub mod qos;
+#[cfg(feature = "enet-1g")]
+mod queue;
+mod statistics;
 
 pub use bd::{IoBuffers, IoSlices, ReceiveBuffers, ReceiveSlices, TransmitBuffers, TransmitSlices};
+
+use core::marker::PhantomData;
 use imxrt_ral as ral;
 
+pub use config::{ConfigError, EnetConfig};
+pub use interrupt::Interrupts;
+pub use link::{LinkError, MiiMode, Speed};
 pub use mdio::miim::{Read as MiimRead, Write as MiimWrite};
+pub use mii::{Mdio, MdioTimeout, MiiError};
+pub use monitor::{LinkEvent, LinkMonitor, MonitorError};
+pub use ptp::{PtpClock, Timestamp};
+#[cfg(feature = "enet-1g")]
+pub use queue::{Queue, QueueError, TxScheme};
 pub use smoltcp;
+pub use statistics::MacStatistics;
+
+#[cfg(feature = "embassy-net-driver")]
+pub use embassy_net_driver;
 
 /// Allows independent transmit and receive functions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Duplex {
     /// Transmit and receive functions cannot overlap.
@@ -118,6 +158,17 @@ const ANY_INSTANCE: u8 = u8::MAX;
 /// An ENET instance without its compile-time instance number.
 type AnyInstance = ral::enet::Instance<{ ANY_INSTANCE }>;
 
+/// Create another handle to the same instance.
+///
+/// # Safety
+///
+/// The handles must not perform conflicting register accesses.
+unsafe fn alias(inst: &AnyInstance) -> AnyInstance {
+    // Safety: points to static MMIO. Caller ensures accesses
+    // do not conflict.
+    unsafe { AnyInstance::new(&**inst) }
+}
+
 /// Discard the instance identifier.
 fn into_any<const N: u8>(inst: ral::enet::Instance<N>) -> AnyInstance {
     // Safety: a properly-constructed instance points
@@ -129,27 +180,140 @@ fn into_any<const N: u8>(inst: ral::enet::Instance<N>) -> AnyInstance {
     }
 }
 
+/// Discard the instance identifier of an ENET_1G instance.
+///
+/// ENET_1G extends the ENET register block. The registers that the two blocks
+/// share have the same offsets.
+#[cfg(feature = "enet-1g")]
+fn into_any_1g<const N: u8>(inst: ral::enet_1g::Instance<N>) -> AnyInstance {
+    // Safety: see into_any. The ENET register block is a prefix-compatible
+    // view of the ENET_1G register block.
+    unsafe {
+        let rb: *const ral::enet_1g::RegisterBlock = &*inst;
+        AnyInstance::new(rb.cast())
+    }
+}
+
+/// View an ENET_1G instance as its extended register block.
+///
+/// # Safety
+///
+/// `enet` must be an ENET_1G instance.
+#[cfg(feature = "enet-1g")]
+unsafe fn as_enet_1g(enet: &ral::enet::RegisterBlock) -> &ral::enet_1g::RegisterBlock {
+    let rb: *const ral::enet::RegisterBlock = enet;
+    // Safety: the caller ensures that this is an ENET_1G instance, so the
+    // extended registers exist.
+    unsafe { &*rb.cast() }
+}
+
+/// The state of an [`Enet`] driver whose MAC is disabled.
+///
+/// Configure the MAC's link in this state.
+pub enum Configuring {}
+
+/// The state of an [`Enet`] driver whose MAC is enabled.
+///
+/// The driver sends and receives frames in this state. While the MAC is
+/// stopped for the link, like after [`unlink`](Enet::unlink), the driver
+/// does not hand out tokens to send or receive frames.
+pub enum Running {}
+
+/// A peripheral instance released from an [`Enet`] driver.
+///
+/// The instance has no instance number. Create a new driver from the
+/// instance that matches the released driver.
+pub enum ReleasedInstance {
+    /// An ENET instance.
+    ///
+    /// Create a driver with [`Enet::new`] or [`Enet::try_new`].
+    Enet(ral::enet::Instance<{ ANY_INSTANCE }>),
+    /// An ENET_1G instance.
+    ///
+    /// Create a driver with [`Enet::new_1g`] or [`Enet::try_new_1g`]. The
+    /// array holds the rings that were attached to queues 1 and 2.
+    #[cfg(feature = "enet-1g")]
+    Enet1g(
+        ral::enet_1g::Instance<{ ANY_INSTANCE }>,
+        [Option<(TransmitSlices<'static>, ReceiveSlices<'static>)>; 2],
+    ),
+}
+
+/// The MAC or its DMA did not stop in time.
+///
+/// The MAC is disabled, but the DMA may still use the descriptor rings, so
+/// the driver did not take them back. [Release](Enet::release) an ENET driver
+/// to reset the ENET. Use [`into_inner`](Self::into_inner) to get the driver.
+pub struct StopTimeout<D = Enet<Configuring>>(D);
+
+impl<D> StopTimeout<D> {
+    /// Returns the driver.
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D> core::fmt::Debug for StopTimeout<D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("StopTimeout")
+    }
+}
+
 /// Ethernet MAC and related functions.
 ///
+/// The driver's state `S` is either [`Configuring`] or [`Running`]. A new driver
+/// is configuring. Once your driver is configured, use [`enable_mac`](Enet::enable_mac)
+/// to enable the transmit and receive datapaths. To change the configuration, use
+/// [`disable_mac`](Enet::disable_mac).
+///
 /// The MDIO interface is always enabled. To generally use the MDIO interface,
-/// use [`MiimRead`] and [`MiimWrite`]. Once your driver is configured, use
-/// [`enable_mac`](Enet::enable_mac) to enable the transmit and receive datapaths.
+/// use [`MiimRead`] and [`MiimWrite`]. To configure the MDIO interface, or to
+/// use it independent of the driver, see [`Mdio`].
+///
+/// By default, the driver does not signal interrupts. To signal interrupts, use
+/// [`enable_interrupts`](Enet::enable_interrupts), then call
+/// [`on_interrupt`](Enet::on_interrupt) from your ENET interrupt handler.
 ///
 /// The MAC implements the `phy` interfaces from [`smoltcp`]. The driver optimizes
 /// for hardware-based checksumming as much as possible, but this only applies to
 /// the network and transport layers.
-pub struct Enet {
+pub struct Enet<S> {
     enet: ral::enet::Instance<{ ANY_INSTANCE }>,
     tx_ring: TransmitSlices<'static>,
     rx_ring: ReceiveSlices<'static>,
+    ptp: ptp::PtpState,
+    tx_timestamps: ptp::TxTimestamps,
+    multicast: filter::HashFilter,
+    accept_all_multicast: bool,
+    unicast: filter::HashFilter,
+    checksum: config::ChecksumOffload,
+    mdio: Option<Mdio>,
+    /// The instance is ENET_1G, which supports RGMII and gigabit speeds.
+    gigabit: bool,
+    #[cfg(feature = "enet-1g")]
+    queues: queue::Queues,
+    state: PhantomData<S>,
 }
 
-impl Enet {
+impl Enet<Configuring> {
     /// Create and initialize an ENET driver.
     ///
     /// This resets and initializes the ENET IP block. However, the MAC
     /// is off when the driver is returned. You're expected to apply other
     /// configurations before enabling the MAC.
+    ///
+    /// The driver uses the default [`EnetConfig`] with this source clock and
+    /// MAC address.
+    ///
+    /// If the receive buffers are smaller than the default maximum frame
+    /// length, the driver accepts frames up to the size of a receive buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source clock cannot produce the MDIO clock and hold
+    /// time, which requires a clock faster than 5MHz and no faster than 320MHz.
+    /// Also panics if the receive buffers are smaller than 64 bytes. Use
+    /// [`try_new`](Self::try_new) to handle these errors.
     pub fn new<const N: u8>(
         enet: ral::enet::Instance<N>,
         tx_ring: TransmitSlices<'static>,
@@ -157,15 +321,90 @@ impl Enet {
         source_clock_hz: u32,
         mac: &[u8; 6],
     ) -> Self {
-        Self::init(into_any(enet), tx_ring, rx_ring, source_clock_hz, mac)
+        let config = default_config(source_clock_hz, mac, rx_ring.mtu());
+        Self::try_new(enet, tx_ring, rx_ring, &config).expect("invalid ENET configuration")
     }
 
-    fn init(
-        enet: AnyInstance,
+    /// Create and initialize an ENET driver with a configuration.
+    ///
+    /// This resets and initializes the ENET IP block. However, the MAC
+    /// is off when the driver is returned.
+    ///
+    /// Returns an error if
+    ///
+    /// - the source clock cannot produce the MDIO clock and hold time.
+    /// - the maximum frame length is less than 64 bytes, or it exceeds
+    ///   the size of a receive buffer.
+    /// - the instance cannot support the link.
+    ///
+    /// The driver checks the configuration before it touches the peripheral.
+    pub fn try_new<const N: u8>(
+        enet: ral::enet::Instance<N>,
+        tx_ring: TransmitSlices<'static>,
+        rx_ring: ReceiveSlices<'static>,
+        config: &EnetConfig,
+    ) -> Result<Self, ConfigError> {
+        let mscr = config.validate(rx_ring.mtu(), false)?;
+        Ok(Self::init(
+            into_any(enet),
+            false,
+            tx_ring,
+            rx_ring,
+            config,
+            mscr,
+        ))
+    }
+
+    /// Create and initialize an ENET_1G driver.
+    ///
+    /// This behaves like [`new`](Self::new). The driver also supports
+    /// [`MiiMode::Rgmii`] and [`Speed::M1000`].
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same reasons as [`new`](Self::new). Use
+    /// [`try_new_1g`](Self::try_new_1g) to handle these errors.
+    #[cfg(feature = "enet-1g")]
+    pub fn new_1g<const N: u8>(
+        enet: ral::enet_1g::Instance<N>,
         tx_ring: TransmitSlices<'static>,
         rx_ring: ReceiveSlices<'static>,
         source_clock_hz: u32,
         mac: &[u8; 6],
+    ) -> Self {
+        let config = default_config(source_clock_hz, mac, rx_ring.mtu());
+        Self::try_new_1g(enet, tx_ring, rx_ring, &config).expect("invalid ENET configuration")
+    }
+
+    /// Create and initialize an ENET_1G driver with a configuration.
+    ///
+    /// This behaves like [`try_new`](Self::try_new). The configuration may
+    /// also use [`MiiMode::Rgmii`] and [`Speed::M1000`].
+    #[cfg(feature = "enet-1g")]
+    pub fn try_new_1g<const N: u8>(
+        enet: ral::enet_1g::Instance<N>,
+        tx_ring: TransmitSlices<'static>,
+        rx_ring: ReceiveSlices<'static>,
+        config: &EnetConfig,
+    ) -> Result<Self, ConfigError> {
+        let mscr = config.validate(rx_ring.mtu(), true)?;
+        Ok(Self::init(
+            into_any_1g(enet),
+            true,
+            tx_ring,
+            rx_ring,
+            config,
+            mscr,
+        ))
+    }
+
+    fn init(
+        enet: AnyInstance,
+        gigabit: bool,
+        tx_ring: TransmitSlices<'static>,
+        rx_ring: ReceiveSlices<'static>,
+        config: &EnetConfig,
+        mscr: config::Mscr,
     ) -> Self {
         // Reset the module.
         ral::modify_reg!(ral::enet, enet, ECR, RESET: 1);
@@ -191,24 +430,18 @@ impl Enet {
         ral::write_reg!(ral::enet, enet, TDSR, tx_ring.as_ptr() as _);
         ral::write_reg!(ral::enet, enet, RDSR, rx_ring.as_ptr() as _);
 
-        const SMI_MDC_FREQUENCY_HZ: u32 = 2_500_000;
-        let mii_speed = source_clock_hz.div_ceil(2 * SMI_MDC_FREQUENCY_HZ) - 1;
-        let hold_time = 10_u32.div_ceil(1_000_000_000 / source_clock_hz) - 1;
-        // TODO no way to enable / disable the MII management frame preamble. Maybe a new method
-        // for the user?
-        ral::modify_reg!(ral::enet, enet, MSCR, HOLDTIME: hold_time, MII_SPEED: mii_speed);
+        // Safety: the MDIO interface only accesses MSCR, MMFR, and EIR[MII].
+        // The driver only accesses those fields when it resets the ENET in
+        // release, and it documents that the reset aborts MDIO transfers.
+        let mdio = Mdio::init(unsafe { alias(&enet) }, config.source_clock_hz, mscr);
 
         ral::modify_reg!(ral::enet, enet, RCR,
-            // Default max frame length without VLAN tags.
-            MAX_FL: 1518,
+            MAX_FL: config.max_frame_length as u32,
             // Since we're providing half-duplex control to the user, we
             // can't also enabled loopback.
             LOOP: 0,
-            // No need to snoop.
-            PROM: 0,
-            // Do not reject broadcast frames; we might be interested
-            // in these.
-            BC_REJ: 0,
+            PROM: config.promiscuous as u32,
+            BC_REJ: config.broadcast_reject as u32,
             // The MAC doesn't supply pause frames to the application.
             PAUFWD: 0,
             // Drop padding, along with the CRC, when supplying frames
@@ -228,10 +461,6 @@ impl Enet {
             FCE: 1,
             // MII or RMII mode; must be set.
             MII_MODE: 1,
-            // Default to MII; users can enable RMII later.
-            RMII_MODE: 0,
-            // Default to 100Mbit/sec; users can throttle later.
-            RMII_10T: 0,
         );
 
         ral::modify_reg!(ral::enet, enet, TCR,
@@ -255,71 +484,92 @@ impl Enet {
         ral::modify_reg!(ral::enet, enet, RACC,
             // Discard frames with MAC errors (checksumming, length, PHY errors).
             LINEDIS: 1,
-            // Discard frames with the wrong checksums for the protocol and headers.
-            PRODIS: 1,
-            IPDIS: 1,
+            // Discard frames with the wrong checksums for the protocol and headers,
+            // unless smoltcp checks the checksums.
+            PRODIS: config.checksum.rx as u32,
+            IPDIS: config.checksum.rx as u32,
             // Discard any padding within a short IP datagram.
             PADREM: 1,
         );
         ral::modify_reg!(ral::enet, enet, TACC,
             // Enable protocol checksums. Assumes that smoltcp sets these fields
             // to zero on our behalf.
-            PROCHK: 1,
+            PROCHK: config.checksum.tx as u32,
             // Enable IP checksum injection into the IPv4 header. Assumes that smoltcp
             // sets these fields to zero on our behalf.
-            IPCHK: 1,
+            IPCHK: config.checksum.tx as u32,
         );
 
         // Commit the MAC address so we can match against it in the receive path.
-        ral::write_reg!(
-            ral::enet,
-            enet,
-            PALR,
-            (mac[0] as u32) << 24 | (mac[1] as u32) << 16 | (mac[2] as u32) << 8 | (mac[3] as u32)
-        );
-        ral::write_reg!(
-            ral::enet,
-            enet,
-            PAUR,
-            (mac[4] as u32) << 24 | (mac[5] as u32) << 16
-        );
+        write_mac(&enet, &config.mac);
 
-        Self {
+        // Reject all other unicast frames until the user adds them to the
+        // hash filter. Multicast filtering is committed below.
+        ral::write_reg!(ral::enet, enet, IAUR, 0);
+        ral::write_reg!(ral::enet, enet, IALR, 0);
+
+        // Validated by the caller.
+        write_link(&enet, gigabit, config.mode, config.speed, config.duplex);
+
+        let mut this = Self {
             enet,
             tx_ring,
             rx_ring,
-        }
+            ptp: ptp::PtpState::new(),
+            tx_timestamps: ptp::TxTimestamps::new(),
+            multicast: filter::HashFilter::new(),
+            accept_all_multicast: config.accept_all_multicast,
+            unicast: filter::HashFilter::new(),
+            checksum: config.checksum,
+            mdio: Some(mdio),
+            gigabit,
+            #[cfg(feature = "enet-1g")]
+            queues: queue::Queues::new(),
+            state: PhantomData,
+        };
+        this.commit_multicast();
+        this
     }
 
-    /// Enable (`true`) or disable (`false`) the MAC.
+    /// Configure the MAC for a link.
     ///
-    /// A disabled MAC cannot receive or send frames. By default, the MAC is disabled,
-    /// and you'll need to enable it once you've completed driver configuration.
-    #[inline]
-    pub fn enable_mac(&mut self, enable: bool) {
-        ral::modify_reg!(ral::enet, self.enet, ECR, ETHEREN: enable as u32);
-        if enable {
-            ral::write_reg!(ral::enet, self.enet, RDAR, RDAR: 1);
-        }
+    /// `mode` is the interface between the MAC and the PHY. `speed` and `duplex`
+    /// describe the link, typically resolved by the PHY through auto-negotiation.
+    /// By default, the driver uses MII at 100Mbit/s, half duplex.
+    ///
+    /// Returns an error if the instance cannot support the link. In this case,
+    /// the MAC's configuration does not change.
+    pub fn set_link(
+        &mut self,
+        mode: MiiMode,
+        speed: Speed,
+        duplex: Duplex,
+    ) -> Result<(), LinkError> {
+        link::validate(self.gigabit, mode, speed, duplex)?;
+        write_link(&self.enet, self.gigabit, mode, speed, duplex);
+        Ok(())
     }
 
-    /// Indicates if the ENET MAC is (`true`) or is not (`false`) enabled.
-    #[inline]
-    pub fn is_mac_enabled(&self) -> bool {
-        ral::read_reg!(ral::enet, self.enet, ECR, ETHEREN == 1)
+    /// Delay (`true`) or don't delay (`false`) the RGMII transmit clock.
+    ///
+    /// Delay the clock if neither the PHY nor the board delay it. By default,
+    /// the clock is not delayed. This has no effect unless the driver
+    /// manages ENET_1G.
+    #[cfg(feature = "enet-1g")]
+    pub fn set_rgmii_tx_clock_delay(&mut self, delay: bool) {
+        if self.gigabit {
+            // Safety: the driver manages ENET_1G.
+            let enet = unsafe { as_enet_1g(&self.enet) };
+            ral::modify_reg!(ral::enet_1g, enet, ECR, TXC_DLY: delay as u32);
+        }
     }
 
     /// Enable (`true`) or disable (`false`) RMII mode.
     ///
     /// By default, the driver is in MII mode.
-    ///
-    /// # Panics
-    ///
-    /// Panics if called while the MAC is enabled.
-    // TODO(mciantyre) enums for MII modes, speeds, duplex?
+    #[deprecated(note = "use set_link, which validates the mode and speed")]
     #[inline]
     pub fn enable_rmii_mode(&mut self, enable: bool) {
-        debug_assert!(!self.is_mac_enabled());
         ral::modify_reg!(ral::enet, self.enet, RCR, RMII_MODE: enable as u32);
     }
 
@@ -328,39 +578,226 @@ impl Enet {
     /// When enabled, the recieve pathway operates in 10Mbit/s.
     /// By default, or when disabled, the receive pathway is at
     /// 100Mbit/s.
-    ///
-    /// # Panics
-    ///
-    /// Panics if called while the MAC is enabled.
-    // TODO(mciantyre) enums for MII modes, speeds, duplex?
+    #[deprecated(note = "use set_link, which validates the mode and speed")]
     #[inline]
     pub fn enable_10t_mode(&mut self, enable: bool) {
-        debug_assert!(!self.is_mac_enabled());
         ral::modify_reg!(ral::enet, self.enet, RCR, RMII_10T: enable as u32);
     }
 
     /// Set the half-/full-duplex operation of the MAC.
     ///
     /// For more information, see the [`Duplex`] documentation.
-    ///
-    /// # Panics
-    ///
-    /// Panics if called while the MAC is enabled.
     #[inline]
     pub fn set_duplex(&mut self, duplex: Duplex) {
-        debug_assert!(!self.is_mac_enabled());
-        match duplex {
-            Duplex::Full => {
-                ral::modify_reg!(ral::enet, self.enet, TCR, FDEN: 1);
-                ral::modify_reg!(ral::enet, self.enet, RCR, DRT: 0);
-            }
-            Duplex::Half => {
-                ral::modify_reg!(ral::enet, self.enet, TCR, FDEN: 0);
-                ral::modify_reg!(ral::enet, self.enet, RCR, DRT: 1);
-            }
+        write_duplex(&self.enet, duplex);
+    }
+
+    /// Enable the MAC.
+    ///
+    /// Once enabled, the MAC sends and receives frames. Make sure that
+    /// you've completed the driver configuration before enabling the MAC.
+    pub fn enable_mac(mut self) -> Enet<Running> {
+        self.start();
+        self.into_state()
+    }
+}
+
+impl Enet<Running> {
+    /// Disable the MAC.
+    ///
+    /// A disabled MAC cannot receive or send frames. Disable the MAC to
+    /// change its link configuration, then [enable](Enet::enable_mac) it
+    /// again.
+    ///
+    /// This finishes sending the current frame, then stops the MAC. Frames
+    /// that were waiting to be sent, and received frames that you have not
+    /// yet read, are dropped. If you were waiting for a transmit timestamp,
+    /// the timestamp is lost.
+    ///
+    /// Returns an error if the MAC or its DMA do not stop in time. The error
+    /// holds the driver.
+    #[expect(
+        clippy::result_large_err,
+        reason = "the error holds the driver, just like the success"
+    )]
+    pub fn disable_mac(mut self) -> Result<Enet<Configuring>, StopTimeout> {
+        match self.stop() {
+            Ok(()) => Ok(self.into_state()),
+            Err(()) => Err(StopTimeout(self.into_state())),
         }
     }
 
+    /// Reconfigure the MAC for a new link.
+    ///
+    /// Use this when the PHY's link changes speed or duplex. A
+    /// [`LinkMonitor`] can do this for you.
+    ///
+    /// This stops the MAC like [`disable_mac`](Self::disable_mac), configures
+    /// the link like [`set_link`](Enet::set_link), then enables the MAC again.
+    /// Frames that were waiting to be sent or received are dropped.
+    ///
+    /// Returns an error if the instance cannot support the link. In this case,
+    /// the MAC keeps running with its previous configuration. If the MAC or
+    /// its DMA do not stop in time, this returns [`LinkError::Timeout`], and
+    /// the MAC stays disabled. The driver does not send or receive frames
+    /// until a later relink succeeds.
+    pub fn relink(&mut self, mode: MiiMode, speed: Speed, duplex: Duplex) -> Result<(), LinkError> {
+        link::validate(self.gigabit, mode, speed, duplex)?;
+        self.stop().map_err(|()| LinkError::Timeout)?;
+        write_link(&self.enet, self.gigabit, mode, speed, duplex);
+        self.start();
+        Ok(())
+    }
+
+    /// Stop the MAC while the link is down.
+    ///
+    /// This stops the MAC like [`disable_mac`](Self::disable_mac), but the
+    /// driver stays running. It does not send or receive frames until you
+    /// [relink](Self::relink) the MAC. A [`LinkMonitor`] can do this for you.
+    ///
+    /// Returns [`LinkError::Timeout`] if the MAC or its DMA do not stop in
+    /// time. In this case, the MAC is disabled, but the driver does not
+    /// rewind its rings.
+    pub fn unlink(&mut self) -> Result<(), LinkError> {
+        self.stop().map_err(|()| LinkError::Timeout)
+    }
+
+    /// Indicates if the MAC is enabled, so the driver may send and receive
+    /// frames.
+    fn mac_enabled(&self) -> bool {
+        ral::read_reg!(ral::enet, self.enet, ECR, ETHEREN == 1)
+    }
+
+    /// Receive a frame along with its IEEE 1588 receive timestamp.
+    ///
+    /// If there's a received frame, `f` is called with the frame and the
+    /// time when the MAC received the frame. Returns `None` if there is no
+    /// received frame, or if the MAC is stopped for the link.
+    ///
+    /// The timestamp combines the hardware's nanoseconds with the driver's
+    /// seconds. It's only meaningful if you receive the frame within one
+    /// second of its arrival. The timestamp is `None` if the driver cannot
+    /// read the time; see [`PtpClock::now`].
+    ///
+    /// Frames received by this method are not visible to smoltcp.
+    pub fn receive_timestamped<R>(
+        &mut self,
+        f: impl FnOnce(&[u8], Option<Timestamp>) -> R,
+    ) -> Option<R> {
+        if !self.mac_enabled() {
+            return None;
+        }
+        let token = self.rx_ring.next_token(RxReady::new(&self.enet))?;
+        let now = ptp::now(&self.enet, &mut self.ptp);
+        let timestamp = now.map(|now| ptp::expand(token.timestamp(), now));
+        Some(smoltcp::phy::RxToken::consume(token, |frame| {
+            f(frame, timestamp)
+        }))
+    }
+
+    /// Transmit a frame, and capture its IEEE 1588 transmit timestamp.
+    ///
+    /// `f` fills the `len`-byte frame. Returns `None` if there's no space
+    /// to transmit the frame, or if the MAC is stopped for the link.
+    /// Otherwise, the frame is queued for transmit, and you can get its
+    /// timestamp from [`tx_timestamp`](Self::tx_timestamp) using the same `id`.
+    ///
+    /// The driver tracks one transmit timestamp at a time. If it's still
+    /// waiting to send a requested smoltcp frame, or if you have not
+    /// collected the previous timestamp, this returns `None` and does not
+    /// send the frame. [Cancel](Self::cancel_tx_timestamp) that timestamp to
+    /// send a new frame.
+    pub fn transmit_timestamped<R>(
+        &mut self,
+        id: u32,
+        len: usize,
+        f: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R> {
+        if !self.mac_enabled() || self.tx_timestamps.is_tracking() {
+            return None;
+        }
+        let mut ready = TxReady::new(&self.enet, &mut self.tx_timestamps);
+        ready.timestamp = Some(id);
+        let token = self.tx_ring.next_token(ready)?;
+        Some(smoltcp::phy::TxToken::consume(token, len, f))
+    }
+}
+
+impl<S> Enet<S> {
+    fn into_state<T>(self) -> Enet<T> {
+        Enet {
+            enet: self.enet,
+            tx_ring: self.tx_ring,
+            rx_ring: self.rx_ring,
+            ptp: self.ptp,
+            tx_timestamps: self.tx_timestamps,
+            multicast: self.multicast,
+            accept_all_multicast: self.accept_all_multicast,
+            unicast: self.unicast,
+            checksum: self.checksum,
+            mdio: self.mdio,
+            gigabit: self.gigabit,
+            #[cfg(feature = "enet-1g")]
+            queues: self.queues,
+            state: PhantomData,
+        }
+    }
+
+    /// Returns the MAC address.
+    pub fn mac_address(&self) -> [u8; 6] {
+        let palr = ral::read_reg!(ral::enet, self.enet, PALR);
+        let paur = ral::read_reg!(ral::enet, self.enet, PAUR);
+        let [a, b, c, d] = palr.to_be_bytes();
+        let [e, f, _, _] = paur.to_be_bytes();
+        [a, b, c, d, e, f]
+    }
+
+    /// Set the MAC address.
+    ///
+    /// The MAC uses this address to filter received frames. It does not
+    /// insert this address into transmitted frames; smoltcp does that. Make
+    /// sure to also update your smoltcp `Interface` with the new address.
+    ///
+    /// You may call this while the MAC is enabled. However, the MAC might
+    /// filter frames received during the update using a mix of the old and
+    /// new address. To avoid that, disable the MAC before you change the address.
+    pub fn set_mac_address(&mut self, mac: &[u8; 6]) {
+        write_mac(&self.enet, mac);
+    }
+
+    /// Access the driver's MDIO interface.
+    ///
+    /// Returns `None` if you took the MDIO interface.
+    #[inline]
+    pub fn mdio(&mut self) -> Option<&mut Mdio> {
+        self.mdio.as_mut()
+    }
+
+    /// Take the driver's MDIO interface.
+    ///
+    /// Use this to manage PHYs independent of the driver. Once taken, the
+    /// driver's [`MiimRead`] and [`MiimWrite`] implementations return
+    /// [`MiiError::Unavailable`]. Returns `None` if you already took the
+    /// MDIO interface.
+    ///
+    /// [Releasing](Enet::release) the driver resets the ENET, which aborts
+    /// a transfer in progress. Don't release the driver while the taken
+    /// interface is transferring.
+    #[inline]
+    pub fn take_mdio(&mut self) -> Option<Mdio> {
+        self.mdio.take()
+    }
+
+    /// Give the driver an MDIO interface, returning the previous interface.
+    ///
+    /// The MDIO interface can come from another ENET instance. For example, if
+    /// ENET2's PHY is connected to ENET1's MDIO pins, give ENET1's MDIO
+    /// interface to the ENET2 driver.
+    #[inline]
+    pub fn replace_mdio(&mut self, mdio: Mdio) -> Option<Mdio> {
+        self.mdio.replace(mdio)
+    }
+
     /// Enable (`true`) or disable (`false`) management information database
     /// (MIB) statistic indicators.
     ///
@@ -377,15 +814,501 @@ impl Enet {
         ral::modify_reg!(ral::enet, self.enet, MIBC, MIB_CLEAR: 1);
         ral::modify_reg!(ral::enet, self.enet, MIBC, MIB_CLEAR: 0);
     }
+
+    /// Read the management information database (MIB) statistic indicators.
+    ///
+    /// The hardware might update counters while you read them. To read
+    /// a consistent snapshot, use [`take_statistics`](Self::take_statistics).
+    #[inline]
+    pub fn statistics(&self) -> MacStatistics {
+        MacStatistics::read(&self.enet)
+    }
+
+    /// Read, then set to zero, all management information database (MIB)
+    /// statistic indicators.
+    ///
+    /// This pauses the MIB while it reads and clears the counters, so the
+    /// snapshot is consistent. The MAC does not count frames while the MIB
+    /// is paused. Once finished, the MIB returns to its previous enabled /
+    /// disabled state.
+    ///
+    /// If the MIB does not pause in time, this reads and clears the counters
+    /// anyway. The snapshot may then miss frames counted while it's read.
+    pub fn take_statistics(&mut self) -> MacStatistics {
+        let disabled = ral::read_reg!(ral::enet, self.enet, MIBC, MIB_DIS);
+        ral::modify_reg!(ral::enet, self.enet, MIBC, MIB_DIS: 1);
+        poll(|| ral::read_reg!(ral::enet, self.enet, MIBC, MIB_IDLE == 1));
+
+        let statistics = MacStatistics::read(&self.enet);
+        self.clear_mib();
+
+        ral::modify_reg!(ral::enet, self.enet, MIBC, MIB_DIS: disabled);
+        statistics
+    }
+
+    /// Enable (`true`) or disable (`false`) promiscuous mode.
+    ///
+    /// In promiscuous mode, the MAC accepts all frames, regardless of their
+    /// destination address. By default, promiscuous mode is disabled.
+    ///
+    /// You may call this while the MAC is enabled.
+    #[inline]
+    pub fn enable_promiscuous_mode(&mut self, enable: bool) {
+        ral::modify_reg!(ral::enet, self.enet, RCR, PROM: enable as u32);
+    }
+
+    /// Enable (`true`) or disable (`false`) broadcast frame rejection.
+    ///
+    /// When enabled, the MAC drops all frames sent to the broadcast
+    /// address, unless it's in promiscuous mode. Note that IPv4 needs
+    /// broadcast frames for ARP. By default, the MAC accepts broadcast
+    /// frames.
+    ///
+    /// You may call this while the MAC is enabled.
+    #[inline]
+    pub fn enable_broadcast_reject(&mut self, enable: bool) {
+        ral::modify_reg!(ral::enet, self.enet, RCR, BC_REJ: enable as u32);
+    }
+
+    /// Receive frames sent to the multicast `address`.
+    ///
+    /// The MAC filters multicast frames using a hash of the address. Addresses
+    /// can share a hash, so you might receive frames for groups that you did
+    /// not join. The driver counts the groups you join, so you should call
+    /// [`leave_multicast`](Self::leave_multicast) once for each call to this
+    /// method.
+    ///
+    /// You may call this while the MAC is enabled.
+    pub fn join_multicast(&mut self, address: &[u8; 6]) {
+        self.multicast.insert(address);
+        self.commit_multicast();
+    }
+
+    /// Stop receiving frames sent to the multicast `address`.
+    ///
+    /// If other joined groups share the address's hash, you'll continue to
+    /// receive frames for `address`. Leaving a group that you did not join
+    /// has no effect, unless the group shares a hash with another joined
+    /// group.
+    ///
+    /// You may call this while the MAC is enabled.
+    pub fn leave_multicast(&mut self, address: &[u8; 6]) {
+        if self.multicast.remove(address) {
+            self.commit_multicast();
+        }
+    }
+
+    /// Accept (`true`) all multicast frames, or only accept frames for
+    /// joined multicast groups (`false`).
+    ///
+    /// By default, the MAC only accepts frames for joined multicast groups.
+    /// The driver continues to count joined groups while it accepts all
+    /// multicast frames.
+    ///
+    /// You may call this while the MAC is enabled.
+    pub fn accept_all_multicast(&mut self, accept: bool) {
+        self.accept_all_multicast = accept;
+        self.commit_multicast();
+    }
+
+    fn commit_multicast(&mut self) {
+        let (upper, lower) = if self.accept_all_multicast {
+            (u32::MAX, u32::MAX)
+        } else {
+            self.multicast.registers()
+        };
+        ral::write_reg!(ral::enet, self.enet, GAUR, upper);
+        ral::write_reg!(ral::enet, self.enet, GALR, lower);
+    }
+
+    /// Receive frames sent to the unicast `address`, in addition to the
+    /// MAC's address.
+    ///
+    /// Like multicast filtering, unicast filtering uses a hash of the
+    /// address, and the driver counts the addresses you add. You should call
+    /// [`remove_unicast_address`](Self::remove_unicast_address) once for each
+    /// call to this method.
+    ///
+    /// You may call this while the MAC is enabled.
+    pub fn add_unicast_address(&mut self, address: &[u8; 6]) {
+        self.unicast.insert(address);
+        self.commit_unicast();
+    }
+
+    /// Stop receiving frames sent to the unicast `address`.
+    ///
+    /// You may call this while the MAC is enabled.
+    pub fn remove_unicast_address(&mut self, address: &[u8; 6]) {
+        if self.unicast.remove(address) {
+            self.commit_unicast();
+        }
+    }
+
+    fn commit_unicast(&mut self) {
+        let (upper, lower) = self.unicast.registers();
+        ral::write_reg!(ral::enet, self.enet, IAUR, upper);
+        ral::write_reg!(ral::enet, self.enet, IALR, lower);
+    }
+
+    /// Enable the interrupt sources in `interrupts`.
+    ///
+    /// Sources that are already enabled remain enabled.
+    #[inline]
+    pub fn enable_interrupts(&mut self, interrupts: Interrupts) {
+        ral::modify_reg!(ral::enet, self.enet, EIMR, |eimr| eimr | interrupts.bits());
+    }
+
+    /// Disable the interrupt sources in `interrupts`.
+    ///
+    /// Sources that are not in `interrupts` keep their state.
+    #[inline]
+    pub fn disable_interrupts(&mut self, interrupts: Interrupts) {
+        ral::modify_reg!(ral::enet, self.enet, EIMR, |eimr| eimr & !interrupts.bits());
+    }
+
+    /// Returns the enabled interrupt sources.
+    #[inline]
+    pub fn enabled_interrupts(&self) -> Interrupts {
+        Interrupts::from_bits_truncate(ral::read_reg!(ral::enet, self.enet, EIMR))
+    }
+
+    /// Handle an ENET interrupt.
+    ///
+    /// Returns the events that triggered the interrupt, and clears those
+    /// events. Only events from enabled interrupt sources are returned
+    /// and cleared.
+    ///
+    /// If [`Interrupts::TS_TIMER`] is enabled, this also counts a new second
+    /// for the [IEEE 1588 timer](Self::ptp_clock).
+    ///
+    /// Don't call this on a driver that's inside an `embassy-net` device.
+    /// The device's interrupt handler already handles the interrupt.
+    pub fn on_interrupt(&mut self) -> Interrupts {
+        let enabled = ral::read_reg!(ral::enet, self.enet, EIMR);
+        let events =
+            Interrupts::from_bits_truncate(ral::read_reg!(ral::enet, self.enet, EIR) & enabled);
+        ral::write_reg!(ral::enet, self.enet, EIR, events.bits());
+        if events.contains(Interrupts::TS_TIMER) {
+            self.ptp.next_second();
+        }
+        events
+    }
+
+    /// Access the IEEE 1588 adjustable timer.
+    ///
+    /// The timer is stopped until you [`start`](PtpClock::start) it.
+    #[inline]
+    pub fn ptp_clock(&mut self) -> PtpClock<'_> {
+        PtpClock::new(&self.enet, &mut self.ptp)
+    }
+
+    /// Capture the IEEE 1588 transmit timestamp of the next frame sent by
+    /// smoltcp with this packet `id`.
+    ///
+    /// smoltcp sockets let you assign a packet ID to the frames they send.
+    /// Once smoltcp sends a frame with this ID, get its timestamp from
+    /// [`tx_timestamp`](Self::tx_timestamp).
+    ///
+    /// The driver tracks one transmit timestamp at a time. If it's still
+    /// waiting to send a requested frame, or if you have not collected the
+    /// previous timestamp, this returns `false` and keeps the previous
+    /// request. [Cancel](Self::cancel_tx_timestamp) that request to make
+    /// a new one.
+    #[cfg(feature = "packetmeta-id")]
+    #[inline]
+    #[must_use]
+    pub fn request_tx_timestamp(&mut self, id: u32) -> bool {
+        if self.tx_timestamps.is_tracking() {
+            return false;
+        }
+        self.tx_timestamps.requested = Some(id);
+        true
+    }
+
+    /// Stop tracking the transmit timestamp.
+    ///
+    /// This drops any request, and any timestamp you have not yet
+    /// collected.
+    #[inline]
+    pub fn cancel_tx_timestamp(&mut self) {
+        self.tx_timestamps.clear();
+    }
+
+    /// Returns the IEEE 1588 transmit timestamp of the frame with this `id`.
+    ///
+    /// Returns `None` if the frame is still waiting to be sent, or if the
+    /// driver is not tracking a frame with this `id`. Once the frame is sent,
+    /// this returns its timestamp, and the driver stops tracking the frame.
+    /// If the driver cannot read the time (see [`PtpClock::now`]), this
+    /// returns `None` instead.
+    ///
+    /// Collect the timestamp within one second of the transmit, and before
+    /// the transmit ring wraps back around to the frame's descriptor.
+    pub fn tx_timestamp(&mut self, id: u32) -> Option<Timestamp> {
+        let (pending, index) = self.tx_timestamps.pending?;
+        if pending != id {
+            return None;
+        }
+        let nanoseconds = self.tx_ring.timestamp(index)?;
+        self.tx_timestamps.pending = None;
+        let now = ptp::now(&self.enet, &mut self.ptp)?;
+        Some(ptp::expand(nanoseconds, now))
+    }
+
+    /// Start the MAC, resuming transmit after a graceful stop.
+    fn start(&mut self) {
+        ral::modify_reg!(ral::enet, self.enet, TCR, GTS: 0);
+        ral::modify_reg!(ral::enet, self.enet, ECR, ETHEREN: 1);
+        ral::write_reg!(ral::enet, self.enet, RDAR, RDAR: 1);
+        #[cfg(feature = "enet-1g")]
+        self.queues.start(&self.enet);
+    }
+
+    /// Stop the MAC, then take back all descriptors.
+    ///
+    /// Disabling the MAC resets the DMA's descriptor pointers to the start of
+    /// each ring. The driver rewinds its rings to match, dropping frames that
+    /// were waiting to be sent or received.
+    ///
+    /// Returns an error if the MAC or its DMA did not stop in time. In this
+    /// case, the MAC is disabled, but the driver does not rewind its rings.
+    fn stop(&mut self) -> Result<(), ()> {
+        if !self.halt() {
+            return Err(());
+        }
+        self.rewind();
+        Ok(())
+    }
+
+    /// Gracefully stop the MAC, then wait for the DMA to go idle.
+    ///
+    /// The MAC is disabled even if it does not stop gracefully. Returns
+    /// `false` if the MAC or its DMA did not stop in time.
+    fn halt(&mut self) -> bool {
+        let mut stopped = true;
+        if ral::read_reg!(ral::enet, self.enet, ECR, ETHEREN == 1) {
+            // Finish the frame being sent, then stop the receive datapath.
+            ral::modify_reg!(ral::enet, self.enet, TCR, GTS: 1);
+            stopped &= poll(|| ral::read_reg!(ral::enet, self.enet, EIR, GRA == 1));
+            ral::write_reg!(ral::enet, self.enet, EIR, GRA: 1);
+
+            ral::modify_reg!(ral::enet, self.enet, ECR, ETHEREN: 0);
+            stopped &= poll(|| ral::read_reg!(ral::enet, self.enet, RCR, GRS == 1));
+        }
+        stopped &= poll(|| {
+            ral::read_reg!(ral::enet, self.enet, TDAR, TDAR == 0)
+                && ral::read_reg!(ral::enet, self.enet, RDAR, RDAR == 0)
+        });
+        #[cfg(feature = "enet-1g")]
+        {
+            stopped &= self.queues.halt(&self.enet);
+        }
+        stopped
+    }
+
+    /// Take back all descriptors, and restart at the start of each ring.
+    ///
+    /// Only call this once the DMA is idle, or reset.
+    fn rewind(&mut self) {
+        self.tx_ring.rewind();
+        self.rx_ring.rewind();
+        #[cfg(feature = "enet-1g")]
+        self.queues.rewind();
+        // The rewound ring dropped the timestamped frame.
+        self.tx_timestamps.pending = None;
+    }
+
+    /// Stop the driver, and release the peripheral and the buffers.
+    ///
+    /// This gracefully stops the transmit and receive datapaths, waits
+    /// for the DMA to go idle, then resets the ENET. If the MAC or DMA do
+    /// not stop in time, the ENET is reset anyway.
+    ///
+    /// The returned peripheral instance has no instance number. You can
+    /// use it, and the buffers, to create a new driver. Frames that were
+    /// waiting to be sent, or waiting to be received, are dropped.
+    ///
+    /// The reset keeps the MDIO configuration, so an MDIO interface that you
+    /// [took](Self::take_mdio) from this driver continues to work. However,
+    /// the reset aborts a transfer in progress, so don't release the driver
+    /// while that interface is transferring. Also, don't create another MDIO
+    /// interface from the returned instance while you're using that interface.
+    ///
+    /// An ENET_1G instance comes with the rings that you attached to its
+    /// other queues.
+    pub fn release(
+        mut self,
+    ) -> (
+        ReleasedInstance,
+        TransmitSlices<'static>,
+        ReceiveSlices<'static>,
+    ) {
+        self.halt();
+        // Keep the MDIO interface running, in case you took it.
+        let mscr = ral::read_reg!(ral::enet, self.enet, MSCR);
+        ral::modify_reg!(ral::enet, self.enet, ECR, RESET: 1);
+        ral::write_reg!(ral::enet, self.enet, MSCR, mscr);
+        // The reset stopped the DMA, even if it was still running.
+        self.rewind();
+        #[cfg(feature = "enet-1g")]
+        if self.gigabit {
+            // Safety: the driver manages ENET_1G.
+            let enet = unsafe { ral::enet_1g::Instance::new(as_enet_1g(&self.enet)) };
+            let queues = self.queues.take_rings();
+            return (
+                ReleasedInstance::Enet1g(enet, queues),
+                self.tx_ring,
+                self.rx_ring,
+            );
+        }
+        (
+            ReleasedInstance::Enet(self.enet),
+            self.tx_ring,
+            self.rx_ring,
+        )
+    }
+}
+
+/// Poll `ready` until it returns `true`, or until we give up.
+///
+/// Returns `false` if we gave up.
+fn poll(mut ready: impl FnMut() -> bool) -> bool {
+    const POLLS: u32 = 1_000_000;
+    (0..POLLS).any(|_| ready())
+}
+
+/// The configuration of [`Enet::new`], which limits the maximum frame
+/// length to the receive buffer size.
+fn default_config(source_clock_hz: u32, mac: &[u8; 6], rx_mtu: usize) -> EnetConfig {
+    let config = EnetConfig::new(source_clock_hz, *mac);
+    let max_frame_length = (config.max_frame_length as usize).min(rx_mtu);
+    config.max_frame_length(max_frame_length as u16)
+}
+
+/// Configure the MAC for a validated link.
+fn write_link(
+    enet: &ral::enet::RegisterBlock,
+    gigabit: bool,
+    mode: MiiMode,
+    speed: Speed,
+    duplex: Duplex,
+) {
+    // In MII mode, the PHY's clocks determine the speed. RMII_10T
+    // applies to the RMII and RGMII modes.
+    let rmii = mode == MiiMode::Rmii;
+    let rmii_10t = mode != MiiMode::Mii && speed == Speed::M10;
+    ral::modify_reg!(ral::enet, enet, RCR,
+        RMII_MODE: rmii as u32,
+        RMII_10T: rmii_10t as u32,
+    );
+    #[cfg(feature = "enet-1g")]
+    if gigabit {
+        // Safety: the caller indicates that this is ENET_1G.
+        let enet = unsafe { as_enet_1g(enet) };
+        ral::modify_reg!(ral::enet_1g, enet, RCR, RGMII_EN: (mode == MiiMode::Rgmii) as u32);
+        ral::modify_reg!(ral::enet_1g, enet, ECR, SPEED: (speed == Speed::M1000) as u32);
+    }
+    // Only ENET_1G supports the RGMII mode and gigabit speeds.
+    #[cfg(not(feature = "enet-1g"))]
+    let _ = gigabit;
+    write_duplex(enet, duplex);
+}
+
+/// Configure the MAC's duplex.
+fn write_duplex(enet: &ral::enet::RegisterBlock, duplex: Duplex) {
+    match duplex {
+        Duplex::Full => {
+            ral::modify_reg!(ral::enet, enet, TCR, FDEN: 1);
+            ral::modify_reg!(ral::enet, enet, RCR, DRT: 0);
+        }
+        Duplex::Half => {
+            ral::modify_reg!(ral::enet, enet, TCR, FDEN: 0);
+            ral::modify_reg!(ral::enet, enet, RCR, DRT: 1);
+        }
+    }
+}
+
+/// Commit the MAC address to the address registers.
+fn write_mac(enet: &ral::enet::RegisterBlock, mac: &[u8; 6]) {
+    ral::write_reg!(
+        ral::enet,
+        enet,
+        PALR,
+        (mac[0] as u32) << 24 | (mac[1] as u32) << 16 | (mac[2] as u32) << 8 | (mac[3] as u32)
+    );
+    ral::write_reg!(
+        ral::enet,
+        enet,
+        PAUR,
+        (mac[4] as u32) << 24 | (mac[5] as u32) << 16
+    );
 }
 
 #[doc(hidden)]
 pub struct TxReady<'a> {
     enet: &'a ral::enet::RegisterBlock,
+    /// Only frames in the driver's ring are timestamped.
+    timestamps: Option<&'a mut ptp::TxTimestamps>,
+    /// Timestamp this frame, tracking it with this ID.
+    timestamp: Option<u32>,
+    #[cfg(feature = "enet-1g")]
+    queue: Queue,
 }
 
-impl TxReady<'_> {
-    fn consume(self) {
+impl<'a> TxReady<'a> {
+    fn new(enet: &'a ral::enet::RegisterBlock, timestamps: &'a mut ptp::TxTimestamps) -> Self {
+        Self {
+            enet,
+            timestamps: Some(timestamps),
+            timestamp: None,
+            #[cfg(feature = "enet-1g")]
+            queue: Queue::Q0,
+        }
+    }
+
+    /// Transmit from one of the ENET_1G queues.
+    #[cfg(feature = "enet-1g")]
+    fn on(enet: &'a ral::enet::RegisterBlock, queue: Queue) -> Self {
+        Self {
+            enet,
+            timestamps: None,
+            timestamp: None,
+            queue,
+        }
+    }
+
+    #[cfg(feature = "packetmeta-id")]
+    fn set_id(&mut self, id: u32) {
+        let Some(timestamps) = self.timestamps.as_deref_mut() else {
+            return;
+        };
+        if timestamps.requested == Some(id) {
+            timestamps.requested = None;
+            self.timestamp = Some(id);
+        }
+    }
+
+    fn wants_timestamp(&self) -> bool {
+        self.timestamp.is_some()
+    }
+
+    fn consume(self, index: usize) {
+        if let Some(timestamps) = self.timestamps {
+            // We're about to reuse the descriptor of a pending
+            // timestamp. That timestamp is lost.
+            if matches!(timestamps.pending, Some((_, pending)) if pending == index) {
+                timestamps.pending = None;
+            }
+            if let Some(id) = self.timestamp {
+                timestamps.pending = Some((id, index));
+            }
+        }
+        #[cfg(feature = "enet-1g")]
+        // Safety: only ENET_1G has rings in the other queues.
+        unsafe {
+            queue::transmit_ready(self.enet, self.queue)
+        };
+        #[cfg(not(feature = "enet-1g"))]
         ral::write_reg!(ral::enet, self.enet, TDAR, TDAR: 1);
     }
 }
@@ -393,57 +1316,69 @@ impl TxReady<'_> {
 #[doc(hidden)]
 pub struct RxReady<'a> {
     enet: &'a ral::enet::RegisterBlock,
+    #[cfg(feature = "enet-1g")]
+    queue: Queue,
 }
 
-impl RxReady<'_> {
-    fn consume(self) {
-        ral::write_reg!(ral::enet, self.enet, RDAR, RDAR: 1);
+impl<'a> RxReady<'a> {
+    fn new(enet: &'a ral::enet::RegisterBlock) -> Self {
+        Self {
+            enet,
+            #[cfg(feature = "enet-1g")]
+            queue: Queue::Q0,
+        }
     }
-}
-
-/// An error during an MII transfer.
-///
-/// Currently, there are no errors. This is a
-/// placeholder for future error handling.
-#[non_exhaustive]
-#[derive(Debug)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum MiiError {}
-
-impl mdio::Read for Enet {
-    type Error = MiiError;
-
-    #[inline]
-    fn read(&mut self, ctrl_bits: u16) -> Result<u16, Self::Error> {
-        // Place the control bits in to the high half-word of the register.
-        let mmfr = (ctrl_bits as u32) << 16;
-        ral::write_reg!(ral::enet, self.enet, MMFR, mmfr);
 
-        while ral::read_reg!(ral::enet, self.enet, EIR, MII == 0) {}
-        ral::write_reg!(ral::enet, self.enet, EIR, MII: 1);
+    /// Receive from one of the ENET_1G queues.
+    #[cfg(feature = "enet-1g")]
+    fn on(enet: &'a ral::enet::RegisterBlock, queue: Queue) -> Self {
+        Self { enet, queue }
+    }
 
-        // Automatically discards control bits.
-        Ok(ral::read_reg!(ral::enet, self.enet, MMFR, DATA) as u16)
+    fn consume(self) {
+        #[cfg(feature = "enet-1g")]
+        // Safety: only ENET_1G has rings in the other queues.
+        unsafe {
+            queue::receive_ready(self.enet, self.queue)
+        };
+        #[cfg(not(feature = "enet-1g"))]
+        ral::write_reg!(ral::enet, self.enet, RDAR, RDAR: 1);
     }
 }
 
-impl mdio::Write for Enet {
-    type Error = MiiError;
-
-    #[inline]
-    fn write(&mut self, ctrl_bits: u16, data_bits: u16) -> Result<(), Self::Error> {
-        // Place control bits into high half-word of register.
-        let mmfr = (ctrl_bits as u32) << 16 | data_bits as u32;
-        ral::write_reg!(ral::enet, self.enet, MMFR, mmfr);
-
-        while ral::read_reg!(ral::enet, self.enet, EIR, MII == 0) {}
-        ral::write_reg!(ral::enet, self.enet, EIR, MII: 1);
+impl Enet<Running> {
+    /// Returns tokens to receive the next frame, and to transmit a frame.
+    ///
+    /// Frames in the ENET_1G queues 2 and 1 are received before frames in
+    /// queue 0. Returns `None` while the MAC is stopped for the link.
+    fn next_tokens(&mut self) -> Option<(bd::RxToken<'_>, bd::TxToken<'_>)> {
+        if !self.mac_enabled() {
+            return None;
+        }
+        let tx = self
+            .tx_ring
+            .next_token(TxReady::new(&self.enet, &mut self.tx_timestamps))?;
+        #[cfg(feature = "enet-1g")]
+        if let Some(rx) = self.queues.next_receive(&self.enet) {
+            return Some((rx, tx));
+        }
+        let rx = self.rx_ring.next_token(RxReady::new(&self.enet))?;
+        Some((rx, tx))
+    }
 
-        Ok(())
+    /// Returns a token to transmit a frame on queue 0.
+    ///
+    /// Returns `None` while the MAC is stopped for the link.
+    fn next_transmit(&mut self) -> Option<bd::TxToken<'_>> {
+        if !self.mac_enabled() {
+            return None;
+        }
+        self.tx_ring
+            .next_token(TxReady::new(&self.enet, &mut self.tx_timestamps))
     }
 }
 
-impl smoltcp::phy::Device for Enet {
+impl smoltcp::phy::Device for Enet<Running> {
     type RxToken<'a> = bd::RxToken<'a>;
     type TxToken<'a> = bd::TxToken<'a>;
 
@@ -451,13 +1386,11 @@ impl smoltcp::phy::Device for Enet {
         &mut self,
         _: smoltcp::time::Instant,
     ) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
-        let tx = self.tx_ring.next_token(TxReady { enet: &self.enet })?;
-        let rx = self.rx_ring.next_token(RxReady { enet: &self.enet })?;
-        Some((rx, tx))
+        self.next_tokens()
     }
 
     fn transmit(&mut self, _: smoltcp::time::Instant) -> Option<Self::TxToken<'_>> {
-        self.tx_ring.next_token(TxReady { enet: &self.enet })
+        self.next_transmit()
     }
 
     fn capabilities(&self) -> smoltcp::phy::DeviceCapabilities {
@@ -468,10 +1401,17 @@ impl smoltcp::phy::Device for Enet {
         caps.max_transmission_unit = mtu;
         caps.max_burst_size = Some(self.tx_ring.len().min(self.rx_ring.len()));
 
-        caps.checksum.ipv4 = smoltcp::phy::Checksum::None;
-        caps.checksum.udp = smoltcp::phy::Checksum::None;
-        caps.checksum.tcp = smoltcp::phy::Checksum::None;
-        caps.checksum.icmpv4 = smoltcp::phy::Checksum::None;
+        // smoltcp handles the checksums that the hardware does not.
+        let checksum = match (self.checksum.rx, self.checksum.tx) {
+            (true, true) => smoltcp::phy::Checksum::None,
+            (true, false) => smoltcp::phy::Checksum::Tx,
+            (false, true) => smoltcp::phy::Checksum::Rx,
+            (false, false) => smoltcp::phy::Checksum::Both,
+        };
+        caps.checksum.ipv4 = checksum;
+        caps.checksum.udp = checksum;
+        caps.checksum.tcp = checksum;
+        caps.checksum.icmpv4 = checksum;
 
         caps
     }
@@ -0,0 +1,113 @@
+//! MAC-to-PHY interface modes and link speeds.
+
+use crate::Duplex;
+
+/// The interface between the MAC and the PHY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MiiMode {
+    /// Media independent interface.
+    ///
+    /// The PHY supplies the transmit and receive clocks, so the clocks
+    /// determine the speed.
+    Mii,
+    /// Reduced media independent interface.
+    ///
+    /// The MAC needs to know the speed.
+    Rmii,
+    /// Reduced gigabit media independent interface.
+    Rgmii,
+}
+
+/// The link speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Speed {
+    /// 10Mbit/s.
+    M10,
+    /// 100Mbit/s.
+    M100,
+    /// 1000Mbit/s.
+    M1000,
+}
+
+/// An invalid link configuration, or a failure to apply it.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LinkError {
+    /// The ENET instance does not support the interface mode.
+    UnsupportedMode(MiiMode),
+    /// The interface mode, or the ENET instance, does not support the speed.
+    UnsupportedSpeed(MiiMode, Speed),
+    /// The speed does not support the duplex.
+    UnsupportedDuplex(Speed, Duplex),
+    /// The MAC or its DMA did not stop in time to change the link.
+    ///
+    /// The MAC is disabled, and the driver did not take back its descriptor
+    /// rings. Release the driver to reset the peripheral.
+    Timeout,
+}
+
+/// Check that a link configuration is valid.
+///
+/// `gigabit` indicates if the ENET instance supports RGMII and gigabit speeds.
+pub(crate) fn validate(
+    gigabit: bool,
+    mode: MiiMode,
+    speed: Speed,
+    duplex: Duplex,
+) -> Result<(), LinkError> {
+    match (mode, speed) {
+        (MiiMode::Rgmii, _) if !gigabit => Err(LinkError::UnsupportedMode(mode)),
+        (MiiMode::Mii | MiiMode::Rmii, Speed::M1000) => {
+            Err(LinkError::UnsupportedSpeed(mode, speed))
+        }
+        (_, Speed::M1000) if matches!(duplex, Duplex::Half) => {
+            Err(LinkError::UnsupportedDuplex(speed, duplex))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkError, MiiMode, Speed, validate};
+    use crate::Duplex;
+
+    #[test]
+    fn fast_ethernet() {
+        for mode in [MiiMode::Mii, MiiMode::Rmii] {
+            for speed in [Speed::M10, Speed::M100] {
+                for duplex in [Duplex::Half, Duplex::Full] {
+                    assert_eq!(validate(false, mode, speed, duplex), Ok(()));
+                    assert_eq!(validate(true, mode, speed, duplex), Ok(()));
+                }
+            }
+            assert_eq!(
+                validate(true, mode, Speed::M1000, Duplex::Full),
+                Err(LinkError::UnsupportedSpeed(mode, Speed::M1000))
+            );
+        }
+    }
+
+    #[test]
+    fn gigabit() {
+        assert_eq!(
+            validate(false, MiiMode::Rgmii, Speed::M100, Duplex::Full),
+            Err(LinkError::UnsupportedMode(MiiMode::Rgmii))
+        );
+        assert_eq!(
+            validate(true, MiiMode::Rgmii, Speed::M1000, Duplex::Full),
+            Ok(())
+        );
+        assert_eq!(
+            validate(true, MiiMode::Rgmii, Speed::M1000, Duplex::Half),
+            Err(LinkError::UnsupportedDuplex(Speed::M1000, Duplex::Half))
+        );
+        assert_eq!(
+            validate(true, MiiMode::Rgmii, Speed::M10, Duplex::Half),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! MDIO transfers through the MII management frame register.
+
+#[cfg(feature = "enet-1g")]
+use crate::into_any_1g;
+use crate::{AnyInstance, ConfigError, Enet, config, into_any, ral};
+
+/// An error during an MII transfer.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MiiError {
+    /// The transfer did not complete before the timeout.
+    ///
+    /// This might indicate that the MDIO clock is not running.
+    Timeout,
+    /// A Clause 22 read returned all ones.
+    ///
+    /// The line's pull-up drives all ones when there's no device
+    /// at the address. A register whose value is all ones also reads
+    /// as this error.
+    NoDevice,
+    /// The driver does not have an MDIO interface.
+    ///
+    /// See [`Enet::take_mdio`].
+    Unavailable,
+}
+
+/// How long to wait for an MDIO transfer, or for a PHY.
+///
+/// By default, the driver gives up after polling a transfer one million
+/// times.
+#[derive(Debug, Clone, Copy)]
+pub enum MdioTimeout {
+    /// Wait forever.
+    Never,
+    /// Give up after polling the transfer this many times.
+    Polls(u32),
+    /// Give up after `ticks` of a timer.
+    ///
+    /// `now` returns the timer's count. The count increments, and it may wrap.
+    Timer {
+        /// Returns the timer's count.
+        now: fn() -> u32,
+        /// How many ticks to wait.
+        ticks: u32,
+    },
+}
+
+impl Default for MdioTimeout {
+    fn default() -> Self {
+        Self::Polls(1_000_000)
+    }
+}
+
+impl MdioTimeout {
+    /// Poll `done` until it returns `true`, or until the timeout.
+    pub(crate) fn wait(self, mut done: impl FnMut() -> bool) -> Result<(), MiiError> {
+        match self.wait_for(|| Ok::<_, core::convert::Infallible>(done())) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(MiiError::Timeout),
+        }
+    }
+
+    /// Poll `done` until it returns `true`, or until the timeout.
+    ///
+    /// Returns `false` on timeout. Returns the first error from `done`.
+    pub(crate) fn wait_for<E>(self, mut done: impl FnMut() -> Result<bool, E>) -> Result<bool, E> {
+        match self {
+            Self::Never => {
+                while !done()? {}
+                Ok(true)
+            }
+            Self::Polls(polls) => {
+                for _ in 0..polls {
+                    if done()? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Self::Timer { now, ticks } => {
+                let start = now();
+                loop {
+                    if done()? {
+                        return Ok(true);
+                    }
+                    if now().wrapping_sub(start) >= ticks {
+                        // One more look, in case we were preempted.
+                        return done();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Perform an MDIO transfer, returning the data bits.
+fn transfer(
+    enet: &ral::enet::RegisterBlock,
+    mmfr: u32,
+    timeout: MdioTimeout,
+) -> Result<u16, MiiError> {
+    // A transfer that previously timed out might have completed since.
+    ral::write_reg!(ral::enet, enet, EIR, MII: 1);
+    ral::write_reg!(ral::enet, enet, MMFR, mmfr);
+
+    timeout.wait(|| ral::read_reg!(ral::enet, enet, EIR, MII == 1))?;
+    ral::write_reg!(ral::enet, enet, EIR, MII: 1);
+
+    // Automatically discards control bits.
+    Ok(ral::read_reg!(ral::enet, enet, MMFR, DATA) as u16)
+}
+
+/// Returns the data of a Clause 22 read, or an error if it's all ones.
+pub(crate) fn clause22_data(data: u16) -> Result<u16, MiiError> {
+    match data {
+        u16::MAX => Err(MiiError::NoDevice),
+        data => Ok(data),
+    }
+}
+
+/// Clause 45 operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum Clause45 {
+    Address = 0b00,
+    Write = 0b01,
+    ReadIncrement = 0b10,
+    Read = 0b11,
+}
+
+/// Build a Clause 45 management frame.
+///
+/// Only the low five bits of `port` and `device` are used.
+const fn clause45_frame(op: Clause45, port: u8, device: u8, data: u16) -> u32 {
+    // ST = 00 selects a Clause 45 frame. TA is always 10.
+    (op as u32) << 28
+        | ((port & 0x1F) as u32) << 23
+        | ((device & 0x1F) as u32) << 18
+        | 0b10 << 16
+        | data as u32
+}
+
+/// The default MDIO configuration for a source clock.
+fn default_mscr(source_clock_hz: u32) -> Result<config::Mscr, ConfigError> {
+    Ok(config::Mscr {
+        mii_speed: config::mii_speed(source_clock_hz, config::MDC_FREQUENCY_HZ)?,
+        hold_time: config::hold_time(source_clock_hz, config::MDIO_HOLD_TIME_NS)?,
+        preamble: true,
+    })
+}
+
+/// An MDIO interface.
+///
+/// Use [`MiimRead`](crate::MiimRead) and [`MiimWrite`](crate::MiimWrite) for
+/// Clause 22 transfers. Use the `clause45_*` methods for Clause 45 transfers.
+///
+/// Every [`Enet`] driver has an MDIO interface, and you may
+/// [take](Enet::take_mdio) it from the driver. You can also create an MDIO
+/// interface from an ENET instance that you don't otherwise use. The MDIO
+/// interface only uses the MDIO-related registers, so you may use it
+/// independent of the driver's data path.
+pub struct Mdio {
+    enet: AnyInstance,
+    source_clock_hz: u32,
+    timeout: MdioTimeout,
+}
+
+impl Mdio {
+    /// Create an MDIO interface from an ENET instance.
+    ///
+    /// `source_clock_hz` is the ENET's clock frequency. The MDIO clock is at
+    /// most 2.5MHz, the hold time is at least 10ns, and the interface sends
+    /// the preamble. This does not reset the ENET.
+    ///
+    /// Returns an error if the source clock cannot produce the MDIO clock and
+    /// hold time.
+    pub fn new<const N: u8>(
+        enet: ral::enet::Instance<N>,
+        source_clock_hz: u32,
+    ) -> Result<Self, ConfigError> {
+        let mscr = default_mscr(source_clock_hz)?;
+        Ok(Self::init(into_any(enet), source_clock_hz, mscr))
+    }
+
+    /// Create an MDIO interface from an ENET_1G instance.
+    ///
+    /// See [`new`](Self::new) for more information.
+    #[cfg(feature = "enet-1g")]
+    pub fn new_1g<const N: u8>(
+        enet: ral::enet_1g::Instance<N>,
+        source_clock_hz: u32,
+    ) -> Result<Self, ConfigError> {
+        let mscr = default_mscr(source_clock_hz)?;
+        Ok(Self::init(into_any_1g(enet), source_clock_hz, mscr))
+    }
+
+    pub(crate) fn init(enet: AnyInstance, source_clock_hz: u32, mscr: config::Mscr) -> Self {
+        ral::modify_reg!(ral::enet, enet, MSCR,
+            HOLDTIME: mscr.hold_time,
+            DIS_PRE: !mscr.preamble as u32,
+            MII_SPEED: mscr.mii_speed,
+        );
+        Self {
+            enet,
+            source_clock_hz,
+            timeout: MdioTimeout::default(),
+        }
+    }
+
+    /// Set how long transfers wait before returning [`MiiError::Timeout`].
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: MdioTimeout) {
+        self.timeout = timeout;
+    }
+
+    /// Set the MDIO clock (MDC) frequency.
+    ///
+    /// The interface selects the fastest frequency that does not exceed `mdc_hz`,
+    /// and returns that frequency. By default, the MDIO clock is at most 2.5MHz.
+    /// Make sure that all devices on the bus support the frequency.
+    ///
+    /// Returns an error if the source clock cannot be divided to `mdc_hz`. In
+    /// this case, the frequency does not change.
+    pub fn set_mdc_frequency(&mut self, mdc_hz: u32) -> Result<u32, ConfigError> {
+        let mii_speed = config::mii_speed(self.source_clock_hz, mdc_hz)?;
+        ral::modify_reg!(ral::enet, self.enet, MSCR, MII_SPEED: mii_speed);
+        Ok(config::mdc_frequency(self.source_clock_hz, mii_speed))
+    }
+
+    /// Set the MDIO output hold time, in nanoseconds.
+    ///
+    /// The interface selects the shortest hold time that is at least
+    /// `hold_time_ns`, and returns that hold time. By default, the hold time
+    /// is at least 10ns.
+    ///
+    /// Returns an error if the hold time is too long for the source clock. In
+    /// this case, the hold time does not change.
+    pub fn set_hold_time(&mut self, hold_time_ns: u32) -> Result<u32, ConfigError> {
+        let hold_time = config::hold_time(self.source_clock_hz, hold_time_ns)?;
+        ral::modify_reg!(ral::enet, self.enet, MSCR, HOLDTIME: hold_time);
+        Ok(config::hold_time_ns(self.source_clock_hz, hold_time))
+    }
+
+    /// Send (`true`) or suppress (`false`) the 32-bit MDIO preamble.
+    ///
+    /// Suppressing the preamble shortens every transfer. Only suppress the
+    /// preamble if all devices on the bus support preamble suppression. By
+    /// default, the interface sends the preamble.
+    #[inline]
+    pub fn enable_preamble(&mut self, enable: bool) {
+        ral::modify_reg!(ral::enet, self.enet, MSCR, DIS_PRE: !enable as u32);
+    }
+
+    /// Read an MMD `register` using Clause 45 MDIO frames.
+    ///
+    /// `port` is the port address, and `device` is the MMD's device address.
+    /// Only the low five bits of these addresses are used. The PHY must support
+    /// Clause 45 frames; for Clause 22 PHYs, see the [`mmd`](crate::mmd) module.
+    pub fn clause45_read(&mut self, port: u8, device: u8, register: u16) -> Result<u16, MiiError> {
+        let address = clause45_frame(Clause45::Address, port, device, register);
+        transfer(&self.enet, address, self.timeout)?;
+        let read = clause45_frame(Clause45::Read, port, device, 0);
+        transfer(&self.enet, read, self.timeout)
+    }
+
+    /// Write `data` to an MMD `register` using Clause 45 MDIO frames.
+    ///
+    /// See [`clause45_read`](Self::clause45_read) for more information.
+    pub fn clause45_write(
+        &mut self,
+        port: u8,
+        device: u8,
+        register: u16,
+        data: u16,
+    ) -> Result<(), MiiError> {
+        let address = clause45_frame(Clause45::Address, port, device, register);
+        transfer(&self.enet, address, self.timeout)?;
+        let write = clause45_frame(Clause45::Write, port, device, data);
+        transfer(&self.enet, write, self.timeout)?;
+        Ok(())
+    }
+
+    /// Read consecutive MMD registers, starting at `register`, using Clause 45
+    /// post-read-increment-address frames.
+    ///
+    /// This fills `data` with one register per element. See
+    /// [`clause45_read`](Self::clause45_read) for more information.
+    pub fn clause45_read_increment(
+        &mut self,
+        port: u8,
+        device: u8,
+        register: u16,
+        data: &mut [u16],
+    ) -> Result<(), MiiError> {
+        let address = clause45_frame(Clause45::Address, port, device, register);
+        transfer(&self.enet, address, self.timeout)?;
+        let read = clause45_frame(Clause45::ReadIncrement, port, device, 0);
+        for data in data {
+            *data = transfer(&self.enet, read, self.timeout)?;
+        }
+        Ok(())
+    }
+}
+
+impl mdio::Read for Mdio {
+    type Error = MiiError;
+
+    #[inline]
+    fn read(&mut self, ctrl_bits: u16) -> Result<u16, Self::Error> {
+        // Place the control bits in to the high half-word of the register.
+        let mmfr = (ctrl_bits as u32) << 16;
+        clause22_data(transfer(&self.enet, mmfr, self.timeout)?)
+    }
+}
+
+impl mdio::Write for Mdio {
+    type Error = MiiError;
+
+    #[inline]
+    fn write(&mut self, ctrl_bits: u16, data_bits: u16) -> Result<(), Self::Error> {
+        // Place control bits into high half-word of register.
+        let mmfr = (ctrl_bits as u32) << 16 | data_bits as u32;
+        transfer(&self.enet, mmfr, self.timeout)?;
+        Ok(())
+    }
+}
+
+impl<S> mdio::Read for Enet<S> {
+    type Error = MiiError;
+
+    #[inline]
+    fn read(&mut self, ctrl_bits: u16) -> Result<u16, Self::Error> {
+        let mdio = self.mdio.as_mut().ok_or(MiiError::Unavailable)?;
+        mdio::Read::read(mdio, ctrl_bits)
+    }
+}
+
+impl<S> mdio::Write for Enet<S> {
+    type Error = MiiError;
+
+    #[inline]
+    fn write(&mut self, ctrl_bits: u16, data_bits: u16) -> Result<(), Self::Error> {
+        let mdio = self.mdio.as_mut().ok_or(MiiError::Unavailable)?;
+        mdio::Write::write(mdio, ctrl_bits, data_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clause45, MdioTimeout, MiiError, clause22_data, clause45_frame};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn clause45_frames() {
+        assert_eq!(
+            clause45_frame(Clause45::Address, 0x1F, 0x01, 0x0905),
+            0x0F86_0905
+        );
+        assert_eq!(
+            clause45_frame(Clause45::Write, 0x01, 0x1E, 0xABCD),
+            0x10FA_ABCD
+        );
+        assert_eq!(
+            clause45_frame(Clause45::ReadIncrement, 0x00, 0x03, 0),
+            0x200E_0000
+        );
+        assert_eq!(clause45_frame(Clause45::Read, 0x02, 0x07, 0), 0x311E_0000);
+        // Upper bits of the addresses are ignored.
+        assert_eq!(
+            clause45_frame(Clause45::Read, 0xE2, 0xE7, 0),
+            clause45_frame(Clause45::Read, 0x02, 0x07, 0)
+        );
+    }
+
+    #[test]
+    fn all_ones() {
+        assert_eq!(clause22_data(0x7949), Ok(0x7949));
+        assert_eq!(clause22_data(0), Ok(0));
+        assert_eq!(clause22_data(u16::MAX), Err(MiiError::NoDevice));
+    }
+
+    #[test]
+    fn polls() {
+        let mut polls = 0;
+        let result = MdioTimeout::Polls(10).wait(|| {
+            polls += 1;
+            false
+        });
+        assert_eq!(result, Err(MiiError::Timeout));
+        assert_eq!(polls, 10);
+
+        let mut polls = 0;
+        let result = MdioTimeout::Polls(10).wait(|| {
+            polls += 1;
+            polls == 3
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(polls, 3);
+    }
+
+    #[test]
+    fn timer_wraps() {
+        static TICKS: AtomicU32 = AtomicU32::new(u32::MAX - 2);
+        fn now() -> u32 {
+            TICKS.fetch_add(1, Ordering::Relaxed)
+        }
+
+        let result = MdioTimeout::Timer { now, ticks: 5 }.wait(|| false);
+        assert_eq!(result, Err(MiiError::Timeout));
+        // One tick to start, then five more.
+        assert_eq!(TICKS.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,223 @@
+//! Indirect access to MMD registers through Clause 22 frames.
+//!
+//! Many Clause 22 PHYs expose extended registers in MDIO manageable devices
+//! (MMDs). Since Clause 22 frames cannot address these registers, the PHY
+//! provides the MMD access control register (13) and the MMD access data
+//! register (14). These functions use those registers to access MMD
+//! registers. They work with any [`MiimRead`] and [`MiimWrite`]
+//! implementation, including [`Enet`](crate::Enet) and [`Mdio`](crate::Mdio).
+//!
+//! ```rust,no_run
+//! use imxrt_enet::{Enet, Running, mmd};
+//!
+//! # fn get_enet() -> Enet<Running> { unimplemented!() }
+//! let mut enet: Enet<Running> = // ...
+//! # get_enet();
+//! const PHY: u8 = 0;
+//! const MMD_PCS: u8 = 3;
+//! const EEE_ABILITY: u16 = 20;
+//!
+//! let eee = mmd::read(&mut enet, PHY, MMD_PCS, EEE_ABILITY);
+//! ```
+//!
+//! If your PHY supports Clause 45 frames, you may prefer
+//! [`Mdio::clause45_read`](crate::Mdio::clause45_read) and
+//! [`Mdio::clause45_write`](crate::Mdio::clause45_write).
+
+use crate::{MiimRead, MiimWrite};
+
+/// MMD access control register.
+const MMD_ACCESS_CONTROL: u8 = 13;
+/// MMD access address / data register.
+const MMD_ACCESS_DATA: u8 = 14;
+
+/// The function of the access data register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+enum Function {
+    /// The data register holds the register address.
+    Address = 0b00 << 14,
+    /// The data register holds the register's data.
+    Data = 0b01 << 14,
+    /// Like `Data`, but the address increments after reads and writes.
+    DataIncrement = 0b10 << 14,
+    /// Like `Data`, but the address increments only after writes.
+    DataWriteIncrement = 0b11 << 14,
+}
+
+/// Select the MMD register at `register`, then prepare for data accesses.
+fn select<M, E>(
+    miim: &mut M,
+    phy: u8,
+    device: u8,
+    register: u16,
+    function: Function,
+) -> Result<(), E>
+where
+    M: MiimWrite<Error = E>,
+{
+    let device = (device & 0x1F) as u16;
+    miim.write(phy, MMD_ACCESS_CONTROL, Function::Address as u16 | device)?;
+    miim.write(phy, MMD_ACCESS_DATA, register)?;
+    miim.write(phy, MMD_ACCESS_CONTROL, function as u16 | device)
+}
+
+/// Read an MMD `register`.
+///
+/// `phy` is the PHY's address, and `device` is the MMD's device address.
+/// Only the low five bits of `device` are used.
+pub fn read<M, E>(miim: &mut M, phy: u8, device: u8, register: u16) -> Result<u16, E>
+where
+    M: MiimRead<Error = E> + MiimWrite<Error = E>,
+{
+    select(miim, phy, device, register, Function::Data)?;
+    miim.read(phy, MMD_ACCESS_DATA)
+}
+
+/// Write `data` to an MMD `register`.
+///
+/// See [`read`] for more information.
+pub fn write<M, E>(miim: &mut M, phy: u8, device: u8, register: u16, data: u16) -> Result<(), E>
+where
+    M: MiimWrite<Error = E>,
+{
+    select(miim, phy, device, register, Function::Data)?;
+    miim.write(phy, MMD_ACCESS_DATA, data)
+}
+
+/// Read consecutive MMD registers, starting at `register`.
+///
+/// This fills `data` with one register per element. It uses the "post
+/// increment on reads and writes" function, so the PHY increments the
+/// register address after every read.
+///
+/// See [`read`] for more information.
+pub fn read_increment<M, E>(
+    miim: &mut M,
+    phy: u8,
+    device: u8,
+    register: u16,
+    data: &mut [u16],
+) -> Result<(), E>
+where
+    M: MiimRead<Error = E> + MiimWrite<Error = E>,
+{
+    select(miim, phy, device, register, Function::DataIncrement)?;
+    for data in data {
+        *data = miim.read(phy, MMD_ACCESS_DATA)?;
+    }
+    Ok(())
+}
+
+/// Write consecutive MMD registers, starting at `register`.
+///
+/// This writes one register per element of `data`. It uses the "post
+/// increment on writes only" function, so the PHY increments the register
+/// address after every write.
+///
+/// See [`read`] for more information.
+pub fn write_increment<M, E>(
+    miim: &mut M,
+    phy: u8,
+    device: u8,
+    register: u16,
+    data: &[u16],
+) -> Result<(), E>
+where
+    M: MiimWrite<Error = E>,
+{
+    select(miim, phy, device, register, Function::DataWriteIncrement)?;
+    for data in data {
+        miim.write(phy, MMD_ACCESS_DATA, *data)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MMD_ACCESS_CONTROL, MMD_ACCESS_DATA};
+    use std::collections::BTreeMap;
+
+    /// A PHY that implements the MMD access registers.
+    #[derive(Default)]
+    struct Phy {
+        control: u16,
+        /// Register address for each device.
+        addresses: BTreeMap<u16, u16>,
+        /// Register values for each (device, register).
+        registers: BTreeMap<(u16, u16), u16>,
+    }
+
+    impl Phy {
+        fn device(&self) -> u16 {
+            self.control & 0x1F
+        }
+        fn function(&self) -> u16 {
+            self.control >> 14
+        }
+        fn address(&mut self) -> &mut u16 {
+            self.addresses.entry(self.control & 0x1F).or_default()
+        }
+    }
+
+    impl crate::MiimRead for Phy {
+        type Error = ();
+        fn read(&mut self, phy: u8, reg: u8) -> Result<u16, ()> {
+            assert_eq!(phy, 1);
+            assert_eq!(reg, MMD_ACCESS_DATA);
+            let address = *self.address();
+            let data = match self.function() {
+                0b00 => address,
+                _ => *self.registers.get(&(self.device(), address)).unwrap_or(&0),
+            };
+            if self.function() == 0b10 {
+                *self.address() += 1;
+            }
+            Ok(data)
+        }
+    }
+
+    impl crate::MiimWrite for Phy {
+        type Error = ();
+        fn write(&mut self, phy: u8, reg: u8, data: u16) -> Result<(), ()> {
+            assert_eq!(phy, 1);
+            match reg {
+                MMD_ACCESS_CONTROL => self.control = data,
+                MMD_ACCESS_DATA if self.function() == 0b00 => *self.address() = data,
+                MMD_ACCESS_DATA => {
+                    let address = *self.address();
+                    self.registers.insert((self.device(), address), data);
+                    if self.function() != 0b01 {
+                        *self.address() += 1;
+                    }
+                }
+                _ => panic!("unexpected register {reg}"),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn read_write() {
+        let mut phy = Phy::default();
+        super::write(&mut phy, 1, 7, 0x3C, 0x0006).unwrap();
+        super::write(&mut phy, 1, 3, 0x3C, 0x1234).unwrap();
+        assert_eq!(super::read(&mut phy, 1, 7, 0x3C), Ok(0x0006));
+        assert_eq!(super::read(&mut phy, 1, 3, 0x3C), Ok(0x1234));
+        // No post increment.
+        assert_eq!(super::read(&mut phy, 1, 3, 0x3C), Ok(0x1234));
+        assert_eq!(phy.addresses[&3], 0x3C);
+    }
+
+    #[test]
+    fn increment() {
+        let mut phy = Phy::default();
+        super::write_increment(&mut phy, 1, 31, 0x10, &[1, 2, 3]).unwrap();
+        assert_eq!(phy.addresses[&31], 0x13);
+
+        let mut data = [0; 4];
+        super::read_increment(&mut phy, 1, 31, 0x0F, &mut data).unwrap();
+        assert_eq!(data, [0, 1, 2, 3]);
+        assert_eq!(phy.addresses[&31], 0x13);
+    }
+}
@@ -0,0 +1,189 @@
+//! Keep the MAC's link configuration in sync with the PHY.
+
+use crate::{
+    Enet, LinkError, MiiError, MiiMode, MiimRead, Running,
+    phy::{Link, Phy},
+};
+
+/// A change in the link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LinkEvent {
+    /// The link is up, and the MAC is configured for the link.
+    ///
+    /// This is also reported when an established link renegotiates to a
+    /// different speed or duplex.
+    LinkUp(Link),
+    /// The link is down.
+    LinkDown,
+}
+
+/// An error from a [`LinkMonitor`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MonitorError<E> {
+    /// An MDIO transfer failed.
+    Mii(E),
+    /// The PHY negotiated a link that the ENET instance cannot support, or
+    /// the MAC did not stop in time to change the link.
+    ///
+    /// See [`Enet::relink`] and [`Enet::unlink`] for the MAC's state after
+    /// each error. The next poll tries again.
+    Link(LinkError),
+}
+
+impl<E> From<E> for MonitorError<E> {
+    fn from(err: E) -> Self {
+        Self::Mii(err)
+    }
+}
+
+/// Watches the PHY's link, and reconfigures the MAC when the link changes.
+///
+/// Call [`poll`](Self::poll) periodically. When the link comes up, or when it
+/// renegotiates to a different speed or duplex, the monitor
+/// [relinks](Enet::relink) the MAC, then reports [`LinkEvent::LinkUp`]. When
+/// the link goes down, the monitor [stops](Enet::unlink) the MAC, then reports
+/// [`LinkEvent::LinkDown`]. Use these events to restart higher-level
+/// protocols, like DHCP.
+///
+/// If your PHY signals link changes on an interrupt pin, you can poll from
+/// that interrupt instead. Acknowledge the PHY's interrupt, using a driver
+/// from the [`phy`](crate::phy) module, then poll the monitor.
+///
+/// ```rust,no_run
+/// use imxrt_enet::{Enet, LinkEvent, LinkMonitor, MiiMode, Running, phy::Phy};
+///
+/// # fn get_enet() -> Enet<Running> { unimplemented!() }
+/// let mut enet: Enet<Running> = // ...
+/// # get_enet();
+/// let mut monitor = LinkMonitor::new(Phy::new(0), MiiMode::Rmii);
+/// loop {
+///     match monitor.poll(&mut enet) {
+///         Ok(Some(LinkEvent::LinkUp(link))) => { /* Restart DHCP... */ }
+///         Ok(Some(LinkEvent::LinkDown)) => { /* Stop using the network... */ }
+///         Ok(None) => {}
+///         Err(err) => { /* Handle the error... */ }
+///     }
+///     // Wait a while...
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinkMonitor {
+    phy: Phy,
+    mode: MiiMode,
+    link: Option<Link>,
+}
+
+impl LinkMonitor {
+    /// Monitor the link of `phy`, which connects to the MAC through `mode`.
+    ///
+    /// The monitor assumes that the link is down. The first poll that
+    /// finds the link up reconfigures the MAC.
+    pub const fn new(phy: Phy, mode: MiiMode) -> Self {
+        Self {
+            phy,
+            mode,
+            link: None,
+        }
+    }
+
+    /// Returns the current link, or `None` if the link is down.
+    pub const fn link(&self) -> Option<Link> {
+        self.link
+    }
+
+    /// Check the PHY's link through the driver's MDIO interface.
+    ///
+    /// Returns the link change, if any. If the driver does not have its
+    /// MDIO interface, this returns [`MiiError::Unavailable`]. In that case,
+    /// use [`poll_with`](Self::poll_with).
+    pub fn poll(
+        &mut self,
+        enet: &mut Enet<Running>,
+    ) -> Result<Option<LinkEvent>, MonitorError<MiiError>> {
+        let link = read_link(self.phy, enet)?;
+        self.update(link, enet)
+    }
+
+    /// Check the PHY's link through `miim`.
+    ///
+    /// Use this when you've [taken](Enet::take_mdio) the MDIO interface from
+    /// the driver. Otherwise, this behaves like [`poll`](Self::poll).
+    pub fn poll_with<M, E>(
+        &mut self,
+        miim: &mut M,
+        enet: &mut Enet<Running>,
+    ) -> Result<Option<LinkEvent>, MonitorError<E>>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let link = read_link(self.phy, miim)?;
+        self.update(link, enet)
+    }
+
+    /// Reconfigure the MAC if the link changed.
+    fn update<E>(
+        &mut self,
+        link: Option<Link>,
+        enet: &mut Enet<Running>,
+    ) -> Result<Option<LinkEvent>, MonitorError<E>> {
+        let event = transition(self.link, link);
+        match event {
+            Some(LinkEvent::LinkUp(link)) => enet.relink(self.mode, link.speed, link.duplex),
+            Some(LinkEvent::LinkDown) => enet.unlink(),
+            None => Ok(()),
+        }
+        .map_err(MonitorError::Link)?;
+        self.link = link;
+        Ok(event)
+    }
+}
+
+/// Returns the PHY's link, or `None` if the link is not ready.
+fn read_link<M, E>(phy: Phy, miim: &mut M) -> Result<Option<Link>, E>
+where
+    M: MiimRead<Error = E>,
+{
+    if !phy.link_status(miim)? {
+        return Ok(None);
+    }
+    phy.resolve(miim)
+}
+
+/// Returns the event for a change from `previous` to `current`.
+fn transition(previous: Option<Link>, current: Option<Link>) -> Option<LinkEvent> {
+    match (previous, current) {
+        (Some(_), None) => Some(LinkEvent::LinkDown),
+        (previous, Some(current)) if previous != Some(current) => Some(LinkEvent::LinkUp(current)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkEvent, transition};
+    use crate::{Duplex, Speed, phy::Link};
+
+    #[test]
+    fn transitions() {
+        let fast = Link {
+            speed: Speed::M100,
+            duplex: Duplex::Full,
+        };
+        let slow = Link {
+            speed: Speed::M10,
+            duplex: Duplex::Half,
+        };
+        assert_eq!(transition(None, None), None);
+        assert_eq!(transition(None, Some(fast)), Some(LinkEvent::LinkUp(fast)));
+        assert_eq!(transition(Some(fast), Some(fast)), None);
+        assert_eq!(
+            transition(Some(fast), Some(slow)),
+            Some(LinkEvent::LinkUp(slow))
+        );
+        assert_eq!(transition(Some(slow), None), Some(LinkEvent::LinkDown));
+    }
+}
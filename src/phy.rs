@@ -0,0 +1,763 @@
+//! IEEE 802.3 Clause 22 PHY management.
+//!
+//! [`Phy`] manages the standard registers that every Clause 22 PHY
+//! implements. It works with any [`MiimRead`] and [`MiimWrite`]
+//! implementation, including [`Enet`](crate::Enet) and [`Mdio`](crate::Mdio).
+//!
+//! ```rust,no_run
+//! use imxrt_enet::{Configuring, Enet, MdioTimeout, MiiMode, phy::{Abilities, Phy}};
+//!
+//! # fn get_enet() -> Enet<Configuring> { unimplemented!() }
+//! let mut enet: Enet<Configuring> = // ...
+//! # get_enet();
+//! let phy = Phy::new(0);
+//! phy.reset(&mut enet, MdioTimeout::Polls(1_000))?;
+//! phy.advertise(&mut enet, Abilities::all())?;
+//! phy.restart_autonegotiation(&mut enet)?;
+//!
+//! // Later, once auto-negotiation completes...
+//! if let Some(link) = phy.resolve(&mut enet)? {
+//!     enet.set_link(MiiMode::Rmii, link.speed, link.duplex).unwrap();
+//!     let enet = enet.enable_mac();
+//! }
+//! # Ok::<(), imxrt_enet::phy::PhyError<imxrt_enet::MiiError>>(())
+//! ```
+//!
+//! The submodules provide drivers for the PHYs found on i.MX RT evaluation
+//! kits and boards. Each driver brings up its PHY in one call, and it manages
+//! the PHY's vendor-specific registers.
+
+pub mod dp83825;
+pub mod ksz8081;
+pub mod rtl8201;
+pub mod rtl8211f;
+
+use crate::{Duplex, MdioTimeout, MiimRead, MiimWrite, Speed};
+
+/// Basic mode control register.
+pub const BMCR: u8 = 0;
+/// Basic mode status register.
+pub const BMSR: u8 = 1;
+/// PHY identifier register 1.
+pub const PHYID1: u8 = 2;
+/// PHY identifier register 2.
+pub const PHYID2: u8 = 3;
+/// Auto-negotiation advertisement register.
+pub const ANAR: u8 = 4;
+/// Auto-negotiation link partner ability register.
+pub const ANLPAR: u8 = 5;
+/// Auto-negotiation expansion register.
+pub const ANER: u8 = 6;
+/// 1000BASE-T control register.
+pub const GBCR: u8 = 9;
+/// 1000BASE-T status register.
+pub const GBSR: u8 = 10;
+/// Extended status register.
+pub const ESR: u8 = 15;
+
+/// BMCR fields.
+mod bmcr {
+    pub const RESET: u16 = 1 << 15;
+    pub const SPEED_LSB: u16 = 1 << 13;
+    pub const AN_ENABLE: u16 = 1 << 12;
+    pub const RESTART_AN: u16 = 1 << 9;
+    pub const FULL_DUPLEX: u16 = 1 << 8;
+    pub const SPEED_MSB: u16 = 1 << 6;
+}
+
+/// BMSR fields.
+mod bmsr {
+    pub const EXTENDED_STATUS: u16 = 1 << 8;
+    pub const AN_COMPLETE: u16 = 1 << 5;
+    pub const LINK_STATUS: u16 = 1 << 2;
+}
+
+/// The page select register, for PHYs with paged registers.
+const PAGE_SELECT: u8 = 0x1F;
+
+/// Selects IEEE 802.3 in ANAR.
+const SELECTOR_802_3: u16 = 0x01;
+/// The technology ability field in ANAR and ANLPAR.
+const TECHNOLOGY_ABILITY: u16 = 0x7F << 5;
+
+/// An error from a PHY operation.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PhyError<E> {
+    /// An MDIO transfer failed.
+    Mii(E),
+    /// The PHY did not finish its reset before the timeout.
+    ResetTimeout,
+    /// There's no PHY at the address.
+    ///
+    /// The bus's pull-up drives all ones when no PHY responds, so the PHY
+    /// identifier reads as all ones.
+    NoDevice,
+}
+
+impl<E> From<E> for PhyError<E> {
+    fn from(err: E) -> Self {
+        Self::Mii(err)
+    }
+}
+
+/// A set of link abilities.
+///
+/// Use this to select the abilities you [advertise](Phy::advertise). It's also
+/// the set of abilities advertised by the [link partner](Phy::partner_abilities).
+///
+/// Combine sets with `|`, and intersect sets with `&`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Abilities(u32);
+
+impl Abilities {
+    /// 10BASE-T, half duplex.
+    pub const HALF_10: Self = Self(1 << 5);
+    /// 10BASE-T, full duplex.
+    pub const FULL_10: Self = Self(1 << 6);
+    /// 100BASE-TX, half duplex.
+    pub const HALF_100: Self = Self(1 << 7);
+    /// 100BASE-TX, full duplex.
+    pub const FULL_100: Self = Self(1 << 8);
+    /// Symmetric pause frames.
+    pub const PAUSE: Self = Self(1 << 10);
+    /// Asymmetric pause frames.
+    pub const ASYM_PAUSE: Self = Self(1 << 11);
+    /// 1000BASE-T, half duplex.
+    pub const HALF_1000: Self = Self(1 << 16);
+    /// 1000BASE-T, full duplex.
+    pub const FULL_1000: Self = Self(1 << 17);
+
+    const ALL: u32 = Self::HALF_10.0
+        | Self::FULL_10.0
+        | Self::HALF_100.0
+        | Self::FULL_100.0
+        | Self::PAUSE.0
+        | Self::ASYM_PAUSE.0
+        | Self::HALF_1000.0
+        | Self::FULL_1000.0;
+
+    /// Returns an empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of all supported abilities.
+    pub const fn all() -> Self {
+        Self(Self::ALL)
+    }
+
+    /// Indicates if the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Indicates if this set contains every member of `other`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Indicates if this set shares any member with `other`.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns the abilities in an ANAR or ANLPAR value.
+    const fn from_base_page(bits: u16) -> Self {
+        Self((bits & TECHNOLOGY_ABILITY) as u32 & Self::ALL)
+    }
+
+    /// Returns the ANAR / ANLPAR technology ability field.
+    const fn base_page(self) -> u16 {
+        self.0 as u16 & TECHNOLOGY_ABILITY
+    }
+
+    /// Returns the abilities in GBCR bits 9 and 8, or GBSR bits 11 and 10.
+    const fn from_gigabit(bits: u16, shift: u32) -> Self {
+        Self((((bits >> shift) & 0b11) as u32) << 16)
+    }
+
+    /// Returns the GBCR abilities in bits 9 and 8.
+    const fn gigabit(self) -> u16 {
+        ((self.0 >> 16) as u16 & 0b11) << 8
+    }
+}
+
+impl core::ops::BitOr for Abilities {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for Abilities {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl core::ops::BitAnd for Abilities {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::Not for Abilities {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0 & Self::ALL)
+    }
+}
+
+/// The direction of the 50MHz RMII reference clock.
+///
+/// The MCU's ENET reference clock direction, configured in the IOMUXC GPR,
+/// must be the opposite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RmiiClock {
+    /// The PHY generates the reference clock, typically from a 25MHz crystal.
+    Output,
+    /// The PHY receives the reference clock, typically from the MCU.
+    Input,
+}
+
+/// A link's speed and duplex.
+///
+/// Use these to [configure the MAC](crate::Enet::set_link).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Link {
+    /// The link speed.
+    pub speed: Speed,
+    /// The link duplex.
+    pub duplex: Duplex,
+}
+
+/// Select the best link that's common to both abilities.
+///
+/// The priority follows IEEE 802.3 Annex 28B.3.
+fn resolve(local: Abilities, partner: Abilities) -> Option<Link> {
+    const PRIORITY: [(Abilities, Speed, Duplex); 6] = [
+        (Abilities::FULL_1000, Speed::M1000, Duplex::Full),
+        (Abilities::HALF_1000, Speed::M1000, Duplex::Half),
+        (Abilities::FULL_100, Speed::M100, Duplex::Full),
+        (Abilities::HALF_100, Speed::M100, Duplex::Half),
+        (Abilities::FULL_10, Speed::M10, Duplex::Full),
+        (Abilities::HALF_10, Speed::M10, Duplex::Half),
+    ];
+    let common = local & partner;
+    PRIORITY
+        .iter()
+        .find(|(ability, _, _)| common.contains(*ability))
+        .map(|&(_, speed, duplex)| Link { speed, duplex })
+}
+
+/// Returns the link forced by BMCR.
+fn forced(bmcr: u16) -> Link {
+    let speed = match (bmcr & bmcr::SPEED_MSB != 0, bmcr & bmcr::SPEED_LSB != 0) {
+        (true, _) => Speed::M1000,
+        (false, true) => Speed::M100,
+        (false, false) => Speed::M10,
+    };
+    let duplex = if bmcr & bmcr::FULL_DUPLEX != 0 {
+        Duplex::Full
+    } else {
+        Duplex::Half
+    };
+    Link { speed, duplex }
+}
+
+/// A Clause 22 PHY.
+///
+/// The PHY only tracks its address. Each method takes the MDIO bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Phy {
+    address: u8,
+}
+
+impl Phy {
+    /// Manage the PHY at `address`.
+    pub const fn new(address: u8) -> Self {
+        Self { address }
+    }
+
+    /// Returns the PHY's address.
+    pub const fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Read a PHY register.
+    pub fn read<M, E>(&self, miim: &mut M, register: u8) -> Result<u16, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        miim.read(self.address, register)
+    }
+
+    /// Write a PHY register.
+    pub fn write<M, E>(&self, miim: &mut M, register: u8, data: u16) -> Result<(), E>
+    where
+        M: MiimWrite<Error = E>,
+    {
+        miim.write(self.address, register, data)
+    }
+
+    /// Read, modify, and write a PHY register.
+    pub fn modify<M, E>(
+        &self,
+        miim: &mut M,
+        register: u8,
+        f: impl FnOnce(u16) -> u16,
+    ) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let value = self.read(miim, register)?;
+        self.write(miim, register, f(value))
+    }
+
+    /// Select `page`, call `f`, then restore the previous page.
+    fn paged<M, E, R>(
+        &self,
+        miim: &mut M,
+        page: u16,
+        f: impl FnOnce(&mut M) -> Result<R, E>,
+    ) -> Result<R, E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let previous = self.read(miim, PAGE_SELECT)?;
+        self.write(miim, PAGE_SELECT, page)?;
+        let result = f(miim);
+        self.write(miim, PAGE_SELECT, previous)?;
+        result
+    }
+
+    /// Read a register on `page`.
+    fn read_paged<M, E>(&self, miim: &mut M, page: u16, register: u8) -> Result<u16, E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.paged(miim, page, |miim| self.read(miim, register))
+    }
+
+    /// Read, modify, and write a register on `page`.
+    fn modify_paged<M, E>(
+        &self,
+        miim: &mut M,
+        page: u16,
+        register: u8,
+        f: impl FnOnce(u16) -> u16,
+    ) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.paged(miim, page, |miim| self.modify(miim, register, f))
+    }
+
+    /// Reset the PHY, call `configure`, then advertise all abilities and
+    /// restart auto-negotiation.
+    fn bring_up<M, E>(
+        &self,
+        miim: &mut M,
+        timeout: MdioTimeout,
+        configure: impl FnOnce(&mut M) -> Result<(), E>,
+    ) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.probe(miim)?;
+        self.reset(miim, timeout)?;
+        configure(miim)?;
+        self.advertise(miim, Abilities::all())?;
+        self.restart_autonegotiation(miim)?;
+        Ok(())
+    }
+
+    /// Returns the 32-bit PHY identifier.
+    ///
+    /// The upper half is PHYID1, and the lower half is PHYID2.
+    pub fn identifier<M, E>(&self, miim: &mut M) -> Result<u32, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let id1 = self.read(miim, PHYID1)?;
+        let id2 = self.read(miim, PHYID2)?;
+        Ok((id1 as u32) << 16 | id2 as u32)
+    }
+
+    /// Check that a PHY responds at the address, and return its identifier.
+    ///
+    /// Returns [`PhyError::NoDevice`] if the identifier reads as all ones.
+    /// The MDIO interface may report the missing PHY first, like
+    /// [`MiiError::NoDevice`](crate::MiiError::NoDevice).
+    pub fn probe<M, E>(&self, miim: &mut M) -> Result<u32, PhyError<E>>
+    where
+        M: MiimRead<Error = E>,
+    {
+        match self.identifier(miim)? {
+            u32::MAX => Err(PhyError::NoDevice),
+            identifier => Ok(identifier),
+        }
+    }
+
+    /// Reset the PHY, then wait for the reset to finish.
+    ///
+    /// `timeout` counts the reads of BMCR. Returns
+    /// [`PhyError::ResetTimeout`] if the reset does not finish in time.
+    pub fn reset<M, E>(&self, miim: &mut M, timeout: MdioTimeout) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.write(miim, BMCR, bmcr::RESET)?;
+        let done = timeout.wait_for(|| Ok::<_, E>(self.read(miim, BMCR)? & bmcr::RESET == 0))?;
+        if done {
+            Ok(())
+        } else {
+            Err(PhyError::ResetTimeout)
+        }
+    }
+
+    /// Indicates if the PHY supports 1000BASE-T registers.
+    fn has_gigabit<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        Ok(self.read(miim, BMSR)? & bmsr::EXTENDED_STATUS != 0)
+    }
+
+    /// Advertise `abilities` during auto-negotiation.
+    ///
+    /// The PHY only advertises the gigabit abilities if it supports gigabit
+    /// speeds. The advertisement takes effect once you
+    /// [restart auto-negotiation](Self::restart_autonegotiation).
+    pub fn advertise<M, E>(&self, miim: &mut M, abilities: Abilities) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.modify(miim, ANAR, |anar| {
+            anar & !(TECHNOLOGY_ABILITY | 0x1F) | abilities.base_page() | SELECTOR_802_3
+        })?;
+        if self.has_gigabit(miim)? {
+            self.modify(miim, GBCR, |gbcr| gbcr & !(0b11 << 8) | abilities.gigabit())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the advertised abilities.
+    pub fn advertised<M, E>(&self, miim: &mut M) -> Result<Abilities, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let mut abilities = Abilities::from_base_page(self.read(miim, ANAR)?);
+        if self.has_gigabit(miim)? {
+            abilities |= Abilities::from_gigabit(self.read(miim, GBCR)?, 8);
+        }
+        Ok(abilities)
+    }
+
+    /// Enable and restart auto-negotiation.
+    pub fn restart_autonegotiation<M, E>(&self, miim: &mut M) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.modify(miim, BMCR, |bmcr| bmcr | bmcr::AN_ENABLE | bmcr::RESTART_AN)
+    }
+
+    /// Indicates if the link is up.
+    ///
+    /// The link status latches low. This reads the status twice, so it
+    /// returns the current status.
+    pub fn link_status<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        self.read(miim, BMSR)?;
+        Ok(self.read(miim, BMSR)? & bmsr::LINK_STATUS != 0)
+    }
+
+    /// Indicates if auto-negotiation is complete.
+    pub fn autonegotiation_complete<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        Ok(self.read(miim, BMSR)? & bmsr::AN_COMPLETE != 0)
+    }
+
+    /// Returns the abilities advertised by the link partner.
+    ///
+    /// This is only meaningful once auto-negotiation is complete.
+    pub fn partner_abilities<M, E>(&self, miim: &mut M) -> Result<Abilities, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let mut abilities = Abilities::from_base_page(self.read(miim, ANLPAR)?);
+        if self.has_gigabit(miim)? {
+            abilities |= Abilities::from_gigabit(self.read(miim, GBSR)?, 10);
+        }
+        Ok(abilities)
+    }
+
+    /// Returns the link's speed and duplex.
+    ///
+    /// If auto-negotiation is enabled, this resolves the link from the
+    /// advertised abilities and the partner's abilities. Returns `None` if
+    /// auto-negotiation is not complete, or if there are no common
+    /// abilities. If auto-negotiation is disabled, this returns the link
+    /// selected in BMCR.
+    ///
+    /// This does not check if the link is [up](Self::link_status).
+    pub fn resolve<M, E>(&self, miim: &mut M) -> Result<Option<Link>, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let bmcr = self.read(miim, BMCR)?;
+        if bmcr & bmcr::AN_ENABLE == 0 {
+            return Ok(Some(forced(bmcr)));
+        }
+        if !self.autonegotiation_complete(miim)? {
+            return Ok(None);
+        }
+        let local = self.advertised(miim)?;
+        let partner = self.partner_abilities(miim)?;
+        Ok(resolve(local, partner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ANAR, Abilities, BMCR, BMSR, GBCR, Link, PAGE_SELECT, PHYID1, PHYID2, Phy, PhyError,
+        RmiiClock, bmcr, dp83825, forced, ksz8081, resolve, rtl8201, rtl8211f,
+    };
+    use crate::{Duplex, MdioTimeout, Speed};
+    use std::collections::BTreeMap;
+
+    /// The address of the PHY on the [`Bus`].
+    pub(super) const ADDRESS: u8 = 2;
+
+    /// A PHY with plain registers, and optional register pages.
+    ///
+    /// Resets finish immediately.
+    pub(super) struct Bus {
+        paged: bool,
+        page: u16,
+        registers: BTreeMap<(u16, u8), u16>,
+    }
+
+    impl Bus {
+        pub(super) fn new(paged: bool) -> Self {
+            Self {
+                paged,
+                page: 0,
+                registers: BTreeMap::new(),
+            }
+        }
+        pub(super) fn page(&self) -> u16 {
+            self.page
+        }
+        pub(super) fn set_page(&mut self, page: u16) {
+            self.page = page;
+        }
+        pub(super) fn get(&self, page: u16, register: u8) -> u16 {
+            *self.registers.get(&(page, register)).unwrap_or(&0)
+        }
+        pub(super) fn set(&mut self, page: u16, register: u8, data: u16) {
+            self.registers.insert((page, register), data);
+        }
+    }
+
+    impl crate::MiimRead for Bus {
+        type Error = ();
+        fn read(&mut self, phy: u8, reg: u8) -> Result<u16, ()> {
+            assert_eq!(phy, ADDRESS);
+            if self.paged && reg == PAGE_SELECT {
+                return Ok(self.page);
+            }
+            Ok(self.get(self.page, reg))
+        }
+    }
+
+    impl crate::MiimWrite for Bus {
+        type Error = ();
+        fn write(&mut self, phy: u8, reg: u8, data: u16) -> Result<(), ()> {
+            assert_eq!(phy, ADDRESS);
+            match reg {
+                PAGE_SELECT if self.paged => self.page = data,
+                BMCR => self.set(self.page, reg, data & !bmcr::RESET),
+                _ => self.set(self.page, reg, data),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registers() {
+        // A typical 10/100 advertisement, with pause.
+        let anar = 0x05E1;
+        let abilities = Abilities::from_base_page(anar);
+        assert_eq!(
+            abilities,
+            Abilities::HALF_10
+                | Abilities::FULL_10
+                | Abilities::HALF_100
+                | Abilities::FULL_100
+                | Abilities::PAUSE
+        );
+        assert_eq!(abilities.base_page() | 0x01, anar);
+        assert_eq!(abilities.gigabit(), 0);
+
+        // GBSR reports the partner's 1000BASE-T abilities in bits 11 and 10.
+        assert_eq!(
+            Abilities::from_gigabit(0x3C00, 10),
+            Abilities::FULL_1000 | Abilities::HALF_1000
+        );
+        assert_eq!(Abilities::from_gigabit(0x0800, 10), Abilities::FULL_1000);
+        assert_eq!(Abilities::FULL_1000.gigabit(), 1 << 9);
+        assert_eq!(Abilities::all().gigabit(), 0b11 << 8);
+    }
+
+    #[test]
+    fn resolution() {
+        let fast = Abilities::all() & !(Abilities::FULL_1000 | Abilities::HALF_1000);
+        assert_eq!(
+            resolve(Abilities::all(), Abilities::all()),
+            Some(Link {
+                speed: Speed::M1000,
+                duplex: Duplex::Full
+            })
+        );
+        assert_eq!(
+            resolve(fast, Abilities::all()),
+            Some(Link {
+                speed: Speed::M100,
+                duplex: Duplex::Full
+            })
+        );
+        assert_eq!(
+            resolve(fast, Abilities::HALF_100 | Abilities::FULL_10),
+            Some(Link {
+                speed: Speed::M100,
+                duplex: Duplex::Half
+            })
+        );
+        assert_eq!(
+            resolve(Abilities::FULL_100, Abilities::HALF_100 | Abilities::PAUSE),
+            None
+        );
+    }
+
+    /// A PHY's registers before and after its driver brings it up.
+    struct BringUp {
+        paged: bool,
+        before: &'static [(u16, u8, u16)],
+        init: fn(&mut Bus) -> Result<(), PhyError<()>>,
+        after: &'static [(u16, u8, u16)],
+    }
+
+    #[test]
+    fn vendor_bring_up() {
+        const TIMEOUT: MdioTimeout = MdioTimeout::Polls(1);
+        let cases = [
+            BringUp {
+                paged: false,
+                before: &[(0, ksz8081::OMSO, 0x0001), (0, ksz8081::PHYCTRL2, 0x8000)],
+                init: |bus| ksz8081::Ksz8081::new(ADDRESS).init(bus, RmiiClock::Input, TIMEOUT),
+                after: &[
+                    (0, ksz8081::OMSO, 0x0002),
+                    (0, ksz8081::PHYCTRL2, 0x8080),
+                    (0, ANAR, 0x0DE1),
+                ],
+            },
+            BringUp {
+                paged: true,
+                before: &[(rtl8201::PAGE_7, rtl8201::RMSR, 0x0FF2)],
+                init: |bus| rtl8201::Rtl8201::new(ADDRESS).init(bus, RmiiClock::Input, TIMEOUT),
+                after: &[(rtl8201::PAGE_7, rtl8201::RMSR, 0x1FFA), (0, ANAR, 0x0DE1)],
+            },
+            BringUp {
+                paged: false,
+                before: &[(0, dp83825::RCSR, 0x0001)],
+                init: |bus| dp83825::Dp83825::new(ADDRESS).init(bus, RmiiClock::Input, TIMEOUT),
+                after: &[(0, dp83825::RCSR, 0x0081), (0, ANAR, 0x0DE1)],
+            },
+            BringUp {
+                paged: true,
+                // Extended status, so the PHY advertises gigabit abilities.
+                before: &[
+                    (0, BMSR, 0x0100),
+                    (rtl8211f::DELAY_PAGE, rtl8211f::TXCR, 0x0009),
+                ],
+                init: |bus| {
+                    let delays = rtl8211f::RgmiiDelays { tx: true, rx: true };
+                    rtl8211f::Rtl8211f::new(ADDRESS).init(bus, delays, TIMEOUT)
+                },
+                after: &[
+                    (rtl8211f::DELAY_PAGE, rtl8211f::TXCR, 0x0109),
+                    (rtl8211f::DELAY_PAGE, rtl8211f::RXCR, 0x0008),
+                    (0, ANAR, 0x0DE1),
+                    (0, GBCR, 0x0300),
+                ],
+            },
+        ];
+        for (index, case) in cases.iter().enumerate() {
+            let mut bus = Bus::new(case.paged);
+            for &(page, register, data) in case.before {
+                bus.set(page, register, data);
+            }
+            (case.init)(&mut bus).unwrap();
+            for &(page, register, data) in case.after {
+                assert_eq!(
+                    bus.get(page, register),
+                    data,
+                    "case {index}, register {register}"
+                );
+            }
+            assert_eq!(bus.page(), 0, "case {index}");
+        }
+    }
+
+    #[test]
+    fn probe() {
+        let mut bus = Bus::new(false);
+        let phy = Phy::new(ADDRESS);
+        bus.set(0, PHYID1, 0xFFFF);
+        bus.set(0, PHYID2, 0xFFFF);
+        assert_eq!(phy.probe(&mut bus), Err(PhyError::NoDevice));
+
+        // A register may still read as all ones.
+        bus.set(0, PHYID1, 0x0022);
+        bus.set(0, PHYID2, 0xFFFF);
+        assert_eq!(phy.probe(&mut bus), Ok(0x0022_FFFF));
+    }
+
+    #[test]
+    fn forced_link() {
+        assert_eq!(
+            forced(0x2100),
+            Link {
+                speed: Speed::M100,
+                duplex: Duplex::Full
+            }
+        );
+        assert_eq!(
+            forced(0x0000),
+            Link {
+                speed: Speed::M10,
+                duplex: Duplex::Half
+            }
+        );
+        assert_eq!(
+            forced(0x0140),
+            Link {
+                speed: Speed::M1000,
+                duplex: Duplex::Full
+            }
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Texas Instruments DP83825I 10/100 PHY.
+//!
+//! The Teensy 4.1 uses this PHY.
+
+use super::{Phy, PhyError, RmiiClock};
+use crate::{MdioTimeout, MiimRead, MiimWrite};
+
+/// PHY specific control register.
+pub const PHYSCR: u8 = 0x11;
+/// MII interrupt status register 1.
+pub const MISR1: u8 = 0x12;
+/// RMII and status register.
+pub const RCSR: u8 = 0x17;
+
+/// PHYSCR fields.
+mod physcr {
+    pub const INTERRUPT_ENABLE: u16 = 1 << 1;
+    pub const INTERRUPT_OUTPUT: u16 = 1 << 0;
+}
+
+/// MISR1 fields.
+///
+/// The status bits are in the upper byte, and the matching enables are in
+/// the lower byte.
+mod misr1 {
+    pub const LINK: u16 = 1 << 13;
+    pub const LINK_ENABLE: u16 = 1 << 5;
+}
+
+/// RCSR fields.
+mod rcsr {
+    pub const CLOCK_50MHZ: u16 = 1 << 7;
+}
+
+/// A DP83825I PHY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dp83825 {
+    phy: Phy,
+}
+
+impl Dp83825 {
+    /// Manage the PHY at `address`.
+    pub const fn new(address: u8) -> Self {
+        Self {
+            phy: Phy::new(address),
+        }
+    }
+
+    /// Returns the generic PHY driver.
+    pub const fn phy(&self) -> Phy {
+        self.phy
+    }
+
+    /// Reset and configure the PHY, then start auto-negotiation.
+    ///
+    /// This selects the reference clock direction, and advertises all
+    /// 10/100 abilities.
+    pub fn init<M, E>(
+        &self,
+        miim: &mut M,
+        clock: RmiiClock,
+        timeout: MdioTimeout,
+    ) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy
+            .bring_up(miim, timeout, |miim| self.set_rmii_clock(miim, clock))
+    }
+
+    /// Select the RMII reference clock direction.
+    ///
+    /// When the PHY receives the clock, it expects 50MHz. Otherwise, it
+    /// generates the clock from a 25MHz reference.
+    pub fn set_rmii_clock<M, E>(&self, miim: &mut M, clock: RmiiClock) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify(miim, RCSR, |rcsr| match clock {
+            RmiiClock::Input => rcsr | rcsr::CLOCK_50MHZ,
+            RmiiClock::Output => rcsr & !rcsr::CLOCK_50MHZ,
+        })
+    }
+
+    /// Enable or disable the link change interrupt.
+    ///
+    /// When enabled, the INT/PWDN pin is the interrupt output.
+    pub fn enable_link_interrupts<M, E>(&self, miim: &mut M, enable: bool) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify(miim, MISR1, |misr1| {
+            if enable {
+                misr1 | misr1::LINK_ENABLE
+            } else {
+                misr1 & !misr1::LINK_ENABLE
+            }
+        })?;
+        let output = physcr::INTERRUPT_ENABLE | physcr::INTERRUPT_OUTPUT;
+        self.phy.modify(miim, PHYSCR, |physcr| {
+            if enable {
+                physcr | output
+            } else {
+                physcr & !output
+            }
+        })
+    }
+
+    /// Acknowledge the PHY's interrupts.
+    ///
+    /// Reading the status clears the interrupts. Returns `true` if the link
+    /// changed.
+    pub fn acknowledge_interrupts<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let status = self.phy.read(miim, MISR1)?;
+        Ok(status & misr1::LINK != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dp83825, MISR1, PHYSCR, RCSR};
+    use crate::phy::{RmiiClock, tests::ADDRESS, tests::Bus};
+
+    #[test]
+    fn rmii_clock() {
+        let mut bus = Bus::new(false);
+        bus.set(0, RCSR, 0x0081);
+        let phy = Dp83825::new(ADDRESS);
+
+        phy.set_rmii_clock(&mut bus, RmiiClock::Output).unwrap();
+        assert_eq!(bus.get(0, RCSR), 0x0001);
+        phy.set_rmii_clock(&mut bus, RmiiClock::Input).unwrap();
+        assert_eq!(bus.get(0, RCSR), 0x0081);
+    }
+
+    #[test]
+    fn interrupts() {
+        let mut bus = Bus::new(false);
+        // Other interrupt enables, and a PHYSCR setting.
+        bus.set(0, MISR1, 0x0004);
+        bus.set(0, PHYSCR, 0x0100);
+        let phy = Dp83825::new(ADDRESS);
+
+        // The interrupt needs both the link enable and the output pin.
+        phy.enable_link_interrupts(&mut bus, true).unwrap();
+        assert_eq!(bus.get(0, MISR1), 0x0024);
+        assert_eq!(bus.get(0, PHYSCR), 0x0103);
+
+        // The status bits are in the upper byte.
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(0, MISR1, 0x0424);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(0, MISR1, 0x2024);
+        assert!(phy.acknowledge_interrupts(&mut bus).unwrap());
+
+        phy.enable_link_interrupts(&mut bus, false).unwrap();
+        assert_eq!(bus.get(0, MISR1), 0x2004);
+        assert_eq!(bus.get(0, PHYSCR), 0x0100);
+    }
+}
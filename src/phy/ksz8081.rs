@@ -0,0 +1,189 @@
+//! Microchip KSZ8081RNB 10/100 PHY.
+//!
+//! The i.MX RT 1050 and 1060 EVKs use this PHY.
+
+use super::{Phy, PhyError, RmiiClock};
+use crate::{MdioTimeout, MiimRead, MiimWrite};
+
+/// Operation mode strap override register.
+pub const OMSO: u8 = 0x16;
+/// Interrupt control / status register.
+pub const ICSR: u8 = 0x1B;
+/// PHY control 1 register.
+pub const PHYCTRL1: u8 = 0x1E;
+/// PHY control 2 register.
+pub const PHYCTRL2: u8 = 0x1F;
+
+/// OMSO fields.
+mod omso {
+    pub const RMII_OVERRIDE: u16 = 1 << 1;
+    pub const MII_OVERRIDE: u16 = 1 << 0;
+}
+
+/// ICSR fields.
+///
+/// The enables are in the upper byte, and the matching status bits are in
+/// the lower byte.
+mod icsr {
+    pub const LINK_DOWN: u16 = 1 << 2;
+    pub const LINK_UP: u16 = 1 << 0;
+    pub const ENABLE_SHIFT: u32 = 8;
+}
+
+/// PHYCTRL2 fields.
+mod phyctrl2 {
+    pub const RMII_50MHZ_CLOCK: u16 = 1 << 7;
+    pub const LED_MODE: u16 = 0b11 << 4;
+}
+
+/// The function of the LED pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u16)]
+pub enum LedMode {
+    /// LED1 shows the speed, and LED0 shows the link and activity.
+    SpeedLinkActivity = 0b00 << 4,
+    /// LED1 shows activity, and LED0 shows the link.
+    ActivityLink = 0b01 << 4,
+}
+
+/// A KSZ8081RNB PHY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ksz8081 {
+    phy: Phy,
+}
+
+impl Ksz8081 {
+    /// Manage the PHY at `address`.
+    pub const fn new(address: u8) -> Self {
+        Self {
+            phy: Phy::new(address),
+        }
+    }
+
+    /// Returns the generic PHY driver.
+    pub const fn phy(&self) -> Phy {
+        self.phy
+    }
+
+    /// Reset and configure the PHY for RMII, then start auto-negotiation.
+    ///
+    /// This overrides the mode straps to select RMII, selects the reference
+    /// clock direction, and advertises all 10/100 abilities.
+    pub fn init<M, E>(
+        &self,
+        miim: &mut M,
+        clock: RmiiClock,
+        timeout: MdioTimeout,
+    ) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.bring_up(miim, timeout, |miim| {
+            self.phy.modify(miim, OMSO, |omso| {
+                omso & !omso::MII_OVERRIDE | omso::RMII_OVERRIDE
+            })?;
+            self.set_rmii_clock(miim, clock)
+        })
+    }
+
+    /// Select the RMII reference clock direction.
+    ///
+    /// When the PHY receives the clock, it expects 50MHz on XI. Otherwise,
+    /// it generates the clock from a 25MHz crystal.
+    pub fn set_rmii_clock<M, E>(&self, miim: &mut M, clock: RmiiClock) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify(miim, PHYCTRL2, |ctrl| match clock {
+            RmiiClock::Input => ctrl | phyctrl2::RMII_50MHZ_CLOCK,
+            RmiiClock::Output => ctrl & !phyctrl2::RMII_50MHZ_CLOCK,
+        })
+    }
+
+    /// Select the function of the LED pins.
+    pub fn set_led_mode<M, E>(&self, miim: &mut M, mode: LedMode) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify(miim, PHYCTRL2, |ctrl| {
+            ctrl & !phyctrl2::LED_MODE | mode as u16
+        })
+    }
+
+    /// Enable or disable the link up and link down interrupts.
+    ///
+    /// The interrupt pin is active low.
+    pub fn enable_link_interrupts<M, E>(&self, miim: &mut M, enable: bool) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let link = (icsr::LINK_UP | icsr::LINK_DOWN) << icsr::ENABLE_SHIFT;
+        self.phy.modify(
+            miim,
+            ICSR,
+            |icsr| {
+                if enable { icsr | link } else { icsr & !link }
+            },
+        )
+    }
+
+    /// Acknowledge the PHY's interrupts.
+    ///
+    /// Reading the status clears the interrupts. Returns `true` if the link
+    /// went up or down.
+    pub fn acknowledge_interrupts<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E>,
+    {
+        let status = self.phy.read(miim, ICSR)?;
+        Ok(status & (icsr::LINK_UP | icsr::LINK_DOWN) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ICSR, Ksz8081, LedMode, PHYCTRL2};
+    use crate::phy::{RmiiClock, tests::ADDRESS, tests::Bus};
+
+    #[test]
+    fn control() {
+        let mut bus = Bus::new(false);
+        // 50MHz clock input, and the speed / link / activity LEDs.
+        bus.set(0, PHYCTRL2, 0x8080);
+        let phy = Ksz8081::new(ADDRESS);
+
+        // Both settings share PHYCTRL2 without disturbing each other.
+        phy.set_led_mode(&mut bus, LedMode::ActivityLink).unwrap();
+        assert_eq!(bus.get(0, PHYCTRL2), 0x8090);
+        phy.set_rmii_clock(&mut bus, RmiiClock::Output).unwrap();
+        assert_eq!(bus.get(0, PHYCTRL2), 0x8010);
+        phy.set_led_mode(&mut bus, LedMode::SpeedLinkActivity)
+            .unwrap();
+        assert_eq!(bus.get(0, PHYCTRL2), 0x8000);
+    }
+
+    #[test]
+    fn interrupts() {
+        let mut bus = Bus::new(false);
+        let phy = Ksz8081::new(ADDRESS);
+
+        // The enables are in the upper byte.
+        phy.enable_link_interrupts(&mut bus, true).unwrap();
+        assert_eq!(bus.get(0, ICSR), 0x0500);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+
+        // Link up, link down, but not other events.
+        bus.set(0, ICSR, 0x0501);
+        assert!(phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(0, ICSR, 0x0504);
+        assert!(phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(0, ICSR, 0x0502);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+
+        bus.set(0, ICSR, 0xFF00);
+        phy.enable_link_interrupts(&mut bus, false).unwrap();
+        assert_eq!(bus.get(0, ICSR), 0xFA00);
+    }
+}
@@ -0,0 +1,159 @@
+//! Realtek RTL8201F 10/100 PHY.
+//!
+//! The i.MX RT 1060 EVKB and 1170 EVK use this PHY for their 10/100 ports.
+//!
+//! Some registers are on page 7. The driver restores the previous page after
+//! each access.
+
+use super::{Phy, PhyError, RmiiClock};
+use crate::{MdioTimeout, MiimRead, MiimWrite};
+
+/// Interrupt indicator register, on page 0.
+pub const ISR: u8 = 0x1E;
+/// The page of the RMII and interrupt enable registers.
+pub const PAGE_7: u16 = 7;
+/// RMII mode setting register, on page 7.
+pub const RMSR: u8 = 0x10;
+/// Interrupt, WOL, and LED function register, on page 7.
+pub const IWELFR: u8 = 0x13;
+
+/// ISR fields.
+mod isr {
+    pub const LINK_CHANGE: u16 = 1 << 11;
+}
+
+/// RMSR fields.
+mod rmsr {
+    pub const CLOCK_INPUT: u16 = 1 << 12;
+    pub const RMII_MODE: u16 = 1 << 3;
+}
+
+/// IWELFR fields.
+mod iwelfr {
+    pub const LINK_CHANGE: u16 = 1 << 13;
+}
+
+/// An RTL8201F PHY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rtl8201 {
+    phy: Phy,
+}
+
+impl Rtl8201 {
+    /// Manage the PHY at `address`.
+    pub const fn new(address: u8) -> Self {
+        Self {
+            phy: Phy::new(address),
+        }
+    }
+
+    /// Returns the generic PHY driver.
+    pub const fn phy(&self) -> Phy {
+        self.phy
+    }
+
+    /// Reset and configure the PHY for RMII, then start auto-negotiation.
+    ///
+    /// This selects RMII mode, selects the reference clock direction, and
+    /// advertises all 10/100 abilities.
+    pub fn init<M, E>(
+        &self,
+        miim: &mut M,
+        clock: RmiiClock,
+        timeout: MdioTimeout,
+    ) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.bring_up(miim, timeout, |miim| {
+            self.phy
+                .modify_paged(miim, PAGE_7, RMSR, |rmsr| rmsr | rmsr::RMII_MODE)?;
+            self.set_rmii_clock(miim, clock)
+        })
+    }
+
+    /// Select the RMII reference clock direction.
+    pub fn set_rmii_clock<M, E>(&self, miim: &mut M, clock: RmiiClock) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy
+            .modify_paged(miim, PAGE_7, RMSR, |rmsr| match clock {
+                RmiiClock::Input => rmsr | rmsr::CLOCK_INPUT,
+                RmiiClock::Output => rmsr & !rmsr::CLOCK_INPUT,
+            })
+    }
+
+    /// Enable or disable the link change interrupt.
+    pub fn enable_link_interrupts<M, E>(&self, miim: &mut M, enable: bool) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify_paged(miim, PAGE_7, IWELFR, |iwelfr| {
+            if enable {
+                iwelfr | iwelfr::LINK_CHANGE
+            } else {
+                iwelfr & !iwelfr::LINK_CHANGE
+            }
+        })
+    }
+
+    /// Acknowledge the PHY's interrupts.
+    ///
+    /// Reading the indicators clears the interrupts. Returns `true` if the
+    /// link changed.
+    pub fn acknowledge_interrupts<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let status = self.phy.read_paged(miim, 0, ISR)?;
+        Ok(status & isr::LINK_CHANGE != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ISR, IWELFR, PAGE_7, RMSR, Rtl8201};
+    use crate::phy::{RmiiClock, tests::ADDRESS, tests::Bus};
+
+    /// A page that the application selected.
+    const PAGE: u16 = 3;
+
+    #[test]
+    fn paged_access() {
+        let mut bus = Bus::new(true);
+        bus.set(PAGE_7, RMSR, 0x1FFA);
+        // The same register offsets on other pages must not change.
+        bus.set(PAGE, RMSR, 0x1234);
+        bus.set_page(PAGE);
+        let phy = Rtl8201::new(ADDRESS);
+
+        phy.set_rmii_clock(&mut bus, RmiiClock::Output).unwrap();
+        assert_eq!(bus.get(PAGE_7, RMSR), 0x0FFA);
+        assert_eq!(bus.get(PAGE, RMSR), 0x1234);
+        assert_eq!(bus.page(), PAGE);
+
+        phy.enable_link_interrupts(&mut bus, true).unwrap();
+        assert_eq!(bus.get(PAGE_7, IWELFR), 0x2000);
+        assert_eq!(bus.page(), PAGE);
+    }
+
+    #[test]
+    fn interrupts() {
+        let mut bus = Bus::new(true);
+        bus.set_page(PAGE);
+        let phy = Rtl8201::new(ADDRESS);
+
+        // The indicators are on page 0, not the selected page.
+        bus.set(PAGE, ISR, 0x0800);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(0, ISR, 0x0800);
+        assert!(phy.acknowledge_interrupts(&mut bus).unwrap());
+        assert_eq!(bus.page(), PAGE);
+
+        bus.set(PAGE_7, IWELFR, 0xFFFF);
+        phy.enable_link_interrupts(&mut bus, false).unwrap();
+        assert_eq!(bus.get(PAGE_7, IWELFR), 0xDFFF);
+    }
+}
@@ -0,0 +1,254 @@
+//! Realtek RTL8211F 10/100/1000 PHY.
+//!
+//! The i.MX RT 1170 EVK uses this PHY for its gigabit port.
+//!
+//! The vendor registers are spread across pages. The driver restores the
+//! previous page after each access.
+
+use super::{Phy, PhyError};
+use crate::{MdioTimeout, MiimRead, MiimWrite};
+
+/// Interrupt enable register, on page 0xA42.
+pub const INER: u8 = 0x12;
+/// The page of [`INER`].
+pub const INER_PAGE: u16 = 0xA42;
+/// Interrupt status register, on page 0xA43.
+pub const INSR: u8 = 0x1D;
+/// The page of [`INSR`].
+pub const INSR_PAGE: u16 = 0xA43;
+/// LED control register, on page 0xD04.
+pub const LCR: u8 = 0x10;
+/// The page of [`LCR`].
+pub const LCR_PAGE: u16 = 0xD04;
+/// RGMII transmit delay register, on page 0xD08.
+pub const TXCR: u8 = 0x11;
+/// RGMII receive delay register, on page 0xD08.
+pub const RXCR: u8 = 0x15;
+/// The page of [`TXCR`] and [`RXCR`].
+pub const DELAY_PAGE: u16 = 0xD08;
+
+/// INER and INSR fields.
+mod inr {
+    pub const LINK_CHANGE: u16 = 1 << 4;
+}
+
+/// LCR fields.
+mod lcr {
+    pub const MODE_B: u16 = 1 << 15;
+    pub const LED_WIDTH: u32 = 5;
+    pub const LED_MASK: u16 = 0x1F;
+}
+
+/// TXCR fields.
+mod txcr {
+    pub const DELAY: u16 = 1 << 8;
+}
+
+/// RXCR fields.
+mod rxcr {
+    pub const DELAY: u16 = 1 << 3;
+}
+
+/// The PHY's internal RGMII clock delays.
+///
+/// Enable a delay if the board does not delay the matching clock. Typically,
+/// either the PHY or the board adds both delays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RgmiiDelays {
+    /// Delay the transmit clock by 2ns.
+    pub tx: bool,
+    /// Delay the receive clock by 2ns.
+    pub rx: bool,
+}
+
+/// The events shown by an LED.
+///
+/// Combine events with `|`. An LED with no events is off.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LedEvents(u16);
+
+impl LedEvents {
+    /// The link is up at 10Mbit/s.
+    pub const LINK_10: Self = Self(1 << 0);
+    /// The link is up at 100Mbit/s.
+    pub const LINK_100: Self = Self(1 << 1);
+    /// The link is up at 1000Mbit/s.
+    pub const LINK_1000: Self = Self(1 << 3);
+    /// The PHY is transmitting or receiving.
+    pub const ACTIVITY: Self = Self(1 << 4);
+
+    /// Returns an empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+}
+
+impl core::ops::BitOr for LedEvents {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// An RTL8211F PHY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rtl8211f {
+    phy: Phy,
+}
+
+impl Rtl8211f {
+    /// Manage the PHY at `address`.
+    pub const fn new(address: u8) -> Self {
+        Self {
+            phy: Phy::new(address),
+        }
+    }
+
+    /// Returns the generic PHY driver.
+    pub const fn phy(&self) -> Phy {
+        self.phy
+    }
+
+    /// Reset and configure the PHY for RGMII, then start auto-negotiation.
+    ///
+    /// This configures the RGMII clock delays, and advertises all
+    /// 10/100/1000 abilities.
+    pub fn init<M, E>(
+        &self,
+        miim: &mut M,
+        delays: RgmiiDelays,
+        timeout: MdioTimeout,
+    ) -> Result<(), PhyError<E>>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy
+            .bring_up(miim, timeout, |miim| self.set_rgmii_delays(miim, delays))
+    }
+
+    /// Enable or disable the internal RGMII clock delays.
+    pub fn set_rgmii_delays<M, E>(&self, miim: &mut M, delays: RgmiiDelays) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let set = |value: u16, bit: u16, enable: bool| {
+            if enable { value | bit } else { value & !bit }
+        };
+        self.phy.modify_paged(miim, DELAY_PAGE, TXCR, |txcr| {
+            set(txcr, txcr::DELAY, delays.tx)
+        })?;
+        self.phy.modify_paged(miim, DELAY_PAGE, RXCR, |rxcr| {
+            set(rxcr, rxcr::DELAY, delays.rx)
+        })
+    }
+
+    /// Select the events shown by LED `index`.
+    ///
+    /// The PHY has three LEDs. Panics if `index` is greater than 2.
+    pub fn set_led<M, E>(&self, miim: &mut M, index: u8, events: LedEvents) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        assert!(index < 3, "RTL8211F LED index out of range");
+        let shift = index as u32 * lcr::LED_WIDTH;
+        self.phy.modify_paged(miim, LCR_PAGE, LCR, |value| {
+            value & !(lcr::LED_MASK << shift) | (events.0 << shift) | lcr::MODE_B
+        })
+    }
+
+    /// Enable or disable the link change interrupt.
+    pub fn enable_link_interrupts<M, E>(&self, miim: &mut M, enable: bool) -> Result<(), E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        self.phy.modify_paged(miim, INER_PAGE, INER, |iner| {
+            if enable {
+                iner | inr::LINK_CHANGE
+            } else {
+                iner & !inr::LINK_CHANGE
+            }
+        })
+    }
+
+    /// Acknowledge the PHY's interrupts.
+    ///
+    /// Reading the status clears the interrupts. Returns `true` if the link
+    /// changed.
+    pub fn acknowledge_interrupts<M, E>(&self, miim: &mut M) -> Result<bool, E>
+    where
+        M: MiimRead<Error = E> + MiimWrite<Error = E>,
+    {
+        let status = self.phy.read_paged(miim, INSR_PAGE, INSR)?;
+        Ok(status & inr::LINK_CHANGE != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DELAY_PAGE, INER, INER_PAGE, INSR, INSR_PAGE, LCR, LCR_PAGE, LedEvents, RXCR, RgmiiDelays,
+        Rtl8211f, TXCR,
+    };
+    use crate::phy::{tests::ADDRESS, tests::Bus};
+
+    /// A page that the application selected.
+    const PAGE: u16 = 0xA46;
+
+    #[test]
+    fn rgmii_delays() {
+        let mut bus = Bus::new(true);
+        bus.set(DELAY_PAGE, TXCR, 0x0109);
+        bus.set(DELAY_PAGE, RXCR, 0x0000);
+        bus.set_page(PAGE);
+        let phy = Rtl8211f::new(ADDRESS);
+
+        let delays = RgmiiDelays {
+            tx: false,
+            rx: true,
+        };
+        phy.set_rgmii_delays(&mut bus, delays).unwrap();
+        assert_eq!(bus.get(DELAY_PAGE, TXCR), 0x0009);
+        assert_eq!(bus.get(DELAY_PAGE, RXCR), 0x0008);
+        assert_eq!(bus.get(PAGE, TXCR), 0);
+        assert_eq!(bus.page(), PAGE);
+    }
+
+    #[test]
+    fn leds() {
+        let mut bus = Bus::new(true);
+        bus.set(LCR_PAGE, LCR, 0x001F << 10);
+        let phy = Rtl8211f::new(ADDRESS);
+
+        // Each LED has five bits. Setting one LED keeps the others.
+        phy.set_led(&mut bus, 1, LedEvents::LINK_1000 | LedEvents::ACTIVITY)
+            .unwrap();
+        phy.set_led(&mut bus, 0, LedEvents::LINK_100).unwrap();
+        assert_eq!(
+            bus.get(LCR_PAGE, LCR),
+            0x8000 | 0x1F << 10 | 0x18 << 5 | 0x02
+        );
+        phy.set_led(&mut bus, 2, LedEvents::empty()).unwrap();
+        assert_eq!(bus.get(LCR_PAGE, LCR), 0x8000 | 0x18 << 5 | 0x02);
+        assert_eq!(bus.page(), 0);
+    }
+
+    #[test]
+    fn interrupts() {
+        let mut bus = Bus::new(true);
+        bus.set_page(PAGE);
+        let phy = Rtl8211f::new(ADDRESS);
+
+        // The enable and the status are on different pages.
+        phy.enable_link_interrupts(&mut bus, true).unwrap();
+        assert_eq!(bus.get(INER_PAGE, INER), 0x0010);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(INER_PAGE, INSR, 0x0010);
+        assert!(!phy.acknowledge_interrupts(&mut bus).unwrap());
+        bus.set(INSR_PAGE, INSR, 0x0010);
+        assert!(phy.acknowledge_interrupts(&mut bus).unwrap());
+        assert_eq!(bus.page(), PAGE);
+    }
+}
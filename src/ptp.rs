@@ -0,0 +1,439 @@
+//! IEEE 1588 adjustable timer.
+//!
+//! The ENET timer is a 32-bit nanosecond counter that wraps once per second.
+//! The driver counts the wraps in software to produce a full timestamp.
+
+#[cfg(feature = "embassy-net-driver")]
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::ral;
+
+const NANOS_PER_SECOND: u32 = 1_000_000_000;
+
+/// The largest value supported by the `ATINC[INC]` field.
+const MAX_INCREMENT: u32 = 0x7F;
+
+/// A time from the IEEE 1588 timer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timestamp {
+    /// Seconds since the timer's epoch.
+    pub seconds: u64,
+    /// Nanoseconds within the second.
+    ///
+    /// This is always less than one billion.
+    pub nanoseconds: u32,
+}
+
+impl Timestamp {
+    /// Create a timestamp from total nanoseconds.
+    ///
+    /// Negative values saturate to zero.
+    pub const fn from_nanos(nanos: i128) -> Self {
+        if nanos <= 0 {
+            return Self {
+                seconds: 0,
+                nanoseconds: 0,
+            };
+        }
+        Self {
+            seconds: (nanos / NANOS_PER_SECOND as i128) as u64,
+            nanoseconds: (nanos % NANOS_PER_SECOND as i128) as u32,
+        }
+    }
+
+    /// Returns the total nanoseconds represented by this timestamp.
+    pub const fn as_nanos(&self) -> i128 {
+        self.seconds as i128 * NANOS_PER_SECOND as i128 + self.nanoseconds as i128
+    }
+}
+
+/// Driver-side state for the timer.
+pub(crate) struct PtpState {
+    /// Seconds counted by software.
+    ///
+    /// If an interrupt handler counts seconds, this is the offset of its
+    /// count.
+    seconds: u64,
+    /// Seconds counted by an interrupt handler.
+    ///
+    /// While this is set, only the handler clears `EIR[TS_TIMER]`.
+    #[cfg(feature = "embassy-net-driver")]
+    counted: Option<&'static AtomicU32>,
+    /// Frequency of the timer's reference clock.
+    reference_clock_hz: u32,
+    /// Nominal nanoseconds per reference clock tick.
+    increment: u32,
+}
+
+impl PtpState {
+    pub(crate) const fn new() -> Self {
+        Self {
+            seconds: 0,
+            #[cfg(feature = "embassy-net-driver")]
+            counted: None,
+            reference_clock_hz: 0,
+            increment: 0,
+        }
+    }
+
+    /// Count a new second, signaled by `EIR[TS_TIMER]`.
+    pub(crate) fn next_second(&mut self) {
+        self.seconds += 1;
+    }
+
+    /// Returns the seconds counted by an interrupt handler.
+    fn counted(&self) -> u64 {
+        #[cfg(feature = "embassy-net-driver")]
+        if let Some(counted) = self.counted {
+            return counted.load(Ordering::Acquire).into();
+        }
+        0
+    }
+
+    fn set_seconds(&mut self, seconds: u64) {
+        self.seconds = seconds.wrapping_sub(self.counted());
+    }
+
+    /// Let an interrupt handler count seconds in `counted`, or take back
+    /// the count if `None`.
+    #[cfg(feature = "embassy-net-driver")]
+    pub(crate) fn count_seconds_in(&mut self, counted: Option<&'static AtomicU32>) {
+        let seconds = self.seconds.wrapping_add(self.counted());
+        self.counted = counted;
+        self.set_seconds(seconds);
+    }
+}
+
+/// Tracks transmit timestamp requests.
+pub(crate) struct TxTimestamps {
+    /// Timestamp the next frame sent with this ID.
+    #[cfg(feature = "packetmeta-id")]
+    pub(crate) requested: Option<u32>,
+    /// The ID and descriptor index of a frame awaiting its timestamp.
+    pub(crate) pending: Option<(u32, usize)>,
+}
+
+impl TxTimestamps {
+    pub(crate) const fn new() -> Self {
+        Self {
+            #[cfg(feature = "packetmeta-id")]
+            requested: None,
+            pending: None,
+        }
+    }
+
+    /// Indicates if the driver is waiting to send a requested frame, or
+    /// if a timestamp has not been collected.
+    pub(crate) fn is_tracking(&self) -> bool {
+        #[cfg(feature = "packetmeta-id")]
+        if self.requested.is_some() {
+            return true;
+        }
+        self.pending.is_some()
+    }
+
+    /// Stop tracking any request, and any timestamp.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Handle to the IEEE 1588 adjustable timer.
+///
+/// Acquire the handle from [`Enet::ptp_clock`](crate::Enet::ptp_clock).
+/// Call [`start`](PtpClock::start) before using the other methods.
+///
+/// The hardware counts nanoseconds, and the driver counts seconds. The
+/// driver observes a new second when you read the time. Make sure to read
+/// the time at least once per second so that the driver doesn't miss a
+/// second.
+pub struct PtpClock<'a> {
+    enet: &'a ral::enet::RegisterBlock,
+    state: &'a mut PtpState,
+}
+
+impl<'a> PtpClock<'a> {
+    pub(crate) fn new(enet: &'a ral::enet::RegisterBlock, state: &'a mut PtpState) -> Self {
+        Self { enet, state }
+    }
+
+    /// Start the timer from zero.
+    ///
+    /// `reference_clock_hz` is the frequency of the timer's input clock.
+    /// The timer advances by the nearest whole number of nanoseconds for each
+    /// reference clock tick, and it corrects any remaining error using the
+    /// timer's correction hardware.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference clock's period is smaller than one nanosecond,
+    /// or larger than 127 nanoseconds.
+    pub fn start(&mut self, reference_clock_hz: u32) {
+        let increment = NANOS_PER_SECOND
+            .checked_div(reference_clock_hz)
+            .unwrap_or(0);
+        assert!(
+            (1..=MAX_INCREMENT).contains(&increment),
+            "unsupported 1588 reference clock"
+        );
+
+        ral::write_reg!(ral::enet, self.enet, ATCR, 0);
+
+        self.state.reference_clock_hz = reference_clock_hz;
+        self.state.increment = increment;
+        self.state.set_seconds(0);
+
+        ral::write_reg!(ral::enet, self.enet, ATPER, NANOS_PER_SECOND);
+        ral::write_reg!(ral::enet, self.enet, ATINC, INC: increment, INC_CORR: increment);
+        ral::write_reg!(ral::enet, self.enet, ATCOR, 0);
+        self.adjust_frequency(0);
+
+        // Raise EIR[TS_TIMER] each time the timer reaches the period.
+        ral::write_reg!(ral::enet, self.enet, ATCR, EN: 1, PEREN: 1);
+        ral::modify_reg!(ral::enet, self.enet, ATCR, RESTART: 1);
+        ral::write_reg!(ral::enet, self.enet, EIR, TS_TIMER: 1);
+    }
+
+    /// Indicates if the timer is running.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        ral::read_reg!(ral::enet, self.enet, ATCR, EN == 1)
+    }
+
+    /// Read the current time.
+    ///
+    /// Returns `None` if the timer is not running, or if it does not capture
+    /// the time, usually because its reference clock is off.
+    pub fn now(&mut self) -> Option<Timestamp> {
+        now(self.enet, self.state)
+    }
+
+    /// Set the current time.
+    pub fn set_time(&mut self, time: Timestamp) {
+        ral::write_reg!(ral::enet, self.enet, ATVR, time.nanoseconds);
+        // Discard any wrap that happened before we set the time.
+        ral::write_reg!(ral::enet, self.enet, EIR, TS_TIMER: 1);
+        self.state.set_seconds(time.seconds);
+    }
+
+    /// Step the current time by `offset` nanoseconds.
+    ///
+    /// A negative offset moves the time backwards. The time saturates at
+    /// zero. Returns `false` if the driver cannot read the time, like
+    /// [`now`](Self::now). In this case, the time does not change.
+    #[must_use]
+    pub fn step(&mut self, offset: i64) -> bool {
+        let Some(now) = self.now() else {
+            return false;
+        };
+        self.set_time(Timestamp::from_nanos(now.as_nanos() + offset as i128));
+        true
+    }
+
+    /// Adjust the timer's frequency by `ppb` parts per billion.
+    ///
+    /// A positive value speeds up the timer, and a negative value slows down
+    /// the timer. Each call replaces the previous adjustment; an adjustment of
+    /// zero runs the timer at the nominal rate.
+    pub fn adjust_frequency(&mut self, ppb: i32) {
+        let (increment, period) =
+            correction(self.state.increment, self.state.reference_clock_hz, ppb);
+        ral::modify_reg!(ral::enet, self.enet, ATINC, INC_CORR: increment);
+        ral::write_reg!(ral::enet, self.enet, ATCOR, COR: period);
+    }
+}
+
+/// Capture and return the timer's nanoseconds.
+///
+/// Returns `None` if the timer is off, or if the capture does not finish.
+fn capture(enet: &ral::enet::RegisterBlock) -> Option<u32> {
+    // Without the timer, or its clock, the capture never finishes.
+    if ral::read_reg!(ral::enet, enet, ATCR, EN == 0) {
+        return None;
+    }
+    ral::modify_reg!(ral::enet, enet, ATCR, CAPTURE: 1);
+    crate::poll(|| ral::read_reg!(ral::enet, enet, ATCR, CAPTURE == 0))
+        .then(|| ral::read_reg!(ral::enet, enet, ATVR))
+}
+
+/// Read the current time, accounting for any new second.
+///
+/// Returns `None` if the driver cannot capture the time.
+pub(crate) fn now(enet: &ral::enet::RegisterBlock, state: &mut PtpState) -> Option<Timestamp> {
+    #[cfg(feature = "embassy-net-driver")]
+    if let Some(counted) = state.counted {
+        return now_counted(enet, state.seconds, counted);
+    }
+    let mut nanoseconds = capture(enet)?;
+    if ral::read_reg!(ral::enet, enet, EIR, TS_TIMER == 1) {
+        ral::write_reg!(ral::enet, enet, EIR, TS_TIMER: 1);
+        state.next_second();
+        // The timer may have wrapped after the first capture.
+        nanoseconds = capture(enet)?;
+    }
+    Some(Timestamp {
+        seconds: state.seconds,
+        nanoseconds,
+    })
+}
+
+/// Read the current time while an interrupt handler counts the seconds.
+///
+/// The handler may count a second at any point. If it does, try again.
+#[cfg(feature = "embassy-net-driver")]
+fn now_counted(
+    enet: &ral::enet::RegisterBlock,
+    offset: u64,
+    counted: &AtomicU32,
+) -> Option<Timestamp> {
+    loop {
+        let count = counted.load(Ordering::Acquire);
+        let mut nanoseconds = capture(enet)?;
+        // A new second that the handler hasn't counted yet.
+        let pending = ral::read_reg!(ral::enet, enet, EIR, TS_TIMER == 1);
+        if pending {
+            // The timer may have wrapped after the first capture.
+            nanoseconds = capture(enet)?;
+        }
+        if counted.load(Ordering::Acquire) == count {
+            return Some(Timestamp {
+                seconds: offset
+                    .wrapping_add(count.into())
+                    .wrapping_add(pending.into()),
+                nanoseconds,
+            });
+        }
+    }
+}
+
+/// Compute the correction increment and correction period.
+///
+/// Every `period` reference clock ticks, the timer advances by the correction
+/// increment instead of the nominal increment. A period of zero disables
+/// correction.
+fn correction(increment: u32, reference_clock_hz: u32, ppb: i32) -> (u32, u32) {
+    let nominal = increment as i64 * reference_clock_hz as i64;
+    let target = NANOS_PER_SECOND as i64 + ppb as i64;
+    let error = target - nominal;
+
+    if error == 0 || reference_clock_hz == 0 {
+        return (increment, 0);
+    }
+
+    let faster = error > 0;
+    let max_step = if faster {
+        MAX_INCREMENT - increment
+    } else {
+        increment
+    };
+
+    // Find the smallest step, and its period, that best approximates the
+    // error. A larger step allows a longer period, which reduces the rounding
+    // error of the period.
+    let error = error.unsigned_abs() as u128;
+    let reference_clock_hz = reference_clock_hz as u128;
+    // (step, period, residual)
+    let mut best: Option<(u32, u128, u128)> = None;
+    for step in 1..=max_step {
+        let extra = step as u128 * reference_clock_hz;
+        let period = ((extra + error / 2) / error).clamp(1, 0x7FFF_FFFF);
+        // Residual error, as a fraction with the denominator `period`.
+        let residual = extra.abs_diff(period * error);
+        let better = match best {
+            None => true,
+            Some((_, best_period, best_residual)) => {
+                residual * best_period < best_residual * period
+            }
+        };
+        if better {
+            best = Some((step, period, residual));
+        }
+        if residual == 0 {
+            break;
+        }
+    }
+
+    match best {
+        None => (increment, 0),
+        Some((step, period, _)) if faster => (increment + step, period as u32),
+        Some((step, period, _)) => (increment - step, period as u32),
+    }
+}
+
+/// Combine a 1588 descriptor timestamp with the current time.
+///
+/// `nanoseconds` must have been sampled less than one second before `now`.
+pub(crate) fn expand(nanoseconds: u32, now: Timestamp) -> Timestamp {
+    let seconds = if nanoseconds > now.nanoseconds {
+        now.seconds.saturating_sub(1)
+    } else {
+        now.seconds
+    };
+    Timestamp {
+        seconds,
+        nanoseconds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Timestamp, correction, expand};
+
+    #[test]
+    fn nominal_rate_needs_no_correction() {
+        assert_eq!(correction(40, 25_000_000, 0), (40, 0));
+        assert_eq!(correction(20, 50_000_000, 0), (20, 0));
+    }
+
+    #[test]
+    fn correct_frequency() {
+        // Speed up by 1000 ppb; 1000 extra nanoseconds per second.
+        assert_eq!(correction(40, 25_000_000, 1000), (41, 25_000));
+        // Slow down by 1000 ppb.
+        assert_eq!(correction(40, 25_000_000, -1000), (39, 25_000));
+    }
+
+    #[test]
+    fn correct_inexact_reference_clock() {
+        // 24MHz has a 41.666ns period. Nominally, the timer runs
+        // 16,000,000ns slow every second.
+        assert_eq!(correction(41, 24_000_000, 0), (43, 3));
+    }
+
+    #[test]
+    fn timestamp_nanos() {
+        let ts = Timestamp::from_nanos(3_000_000_007);
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: 3,
+                nanoseconds: 7
+            }
+        );
+        assert_eq!(ts.as_nanos(), 3_000_000_007);
+        assert_eq!(Timestamp::from_nanos(-5), Timestamp::default());
+    }
+
+    #[test]
+    fn expand_descriptor_timestamp() {
+        let now = Timestamp {
+            seconds: 10,
+            nanoseconds: 500,
+        };
+        assert_eq!(
+            expand(400, now),
+            Timestamp {
+                seconds: 10,
+                nanoseconds: 400
+            }
+        );
+        assert_eq!(
+            expand(999_999_000, now),
+            Timestamp {
+                seconds: 9,
+                nanoseconds: 999_999_000
+            }
+        );
+    }
+}
@@ -0,0 +1,590 @@
+//! Driver for the i.MX RT 1170's ENET_QOS.
+//!
+//! ENET_QOS is a different MAC than ENET and ENET_1G. It has its own
+//! descriptor format, so it uses its own [`TransmitBuffers`] and
+//! [`ReceiveBuffers`]. Otherwise, the [`EnetQos`] driver works like the
+//! [`Enet`](crate::Enet) driver: construct it, configure it, enable it, then
+//! use it with smoltcp.
+//!
+//! The driver uses one DMA channel and one transmit and receive queue. It
+//! supports checksum offload and MDIO transfers. It does not support IEEE 1588
+//! timestamps, interrupts, hash filtering, or MAC statistics.
+//!
+//! This driver does not select the interface between the MAC and the PHY.
+//! Select MII, RMII, or RGMII in the IOMUXC GPR, and enable the ENET_QOS
+//! clocks, before you construct the driver. The MAC's reset needs the
+//! interface clocks, so the PHY, or the clock tree, must supply them.
+//!
+//! ```rust,no_run
+//! use static_cell::ConstStaticCell;
+//! use imxrt_enet::qos::{EnetQos, ReceiveBuffers, TransmitBuffers};
+//! use imxrt_enet::{Duplex, MiiMode, Speed};
+//!
+//! static RX_BUFFERS: ConstStaticCell<ReceiveBuffers<4>> =
+//!     ConstStaticCell::new(ReceiveBuffers::new());
+//! static TX_BUFFERS: ConstStaticCell<TransmitBuffers<4>> =
+//!     ConstStaticCell::new(TransmitBuffers::new());
+//!
+//! const MAC: [u8; 6] = // Your MAC address.
+//! # [6, 5, 4, 3, 2, 1];
+//! const CSR_CLK_HZ: u32 = // Your ENET_QOS bus clock frequency.
+//! # 240_000_000;
+//!
+//! let qos: imxrt_ral::enet_qos::ENET_QOS = // Your peripheral instance.
+//! # unsafe { imxrt_ral::enet_qos::ENET_QOS::instance() };
+//!
+//! let mut qos = EnetQos::new(
+//!     qos,
+//!     TX_BUFFERS.take().take(),
+//!     RX_BUFFERS.take().take(),
+//!     CSR_CLK_HZ,
+//!     &MAC,
+//! ).unwrap();
+//!
+//! // Initialize your PHY using the driver's MDIO interface,
+//! // then configure the MAC for the PHY's link.
+//! qos.set_link(MiiMode::Rgmii, Speed::M1000, Duplex::Full).unwrap();
+//!
+//! let mut qos = qos.enable_mac();
+//! // Use the driver with smoltcp.
+//! ```
+
+mod bd;
+
+pub use bd::{IoBuffers, IoSlices, ReceiveBuffers, TransmitBuffers};
+
+use core::marker::PhantomData;
+
+use crate::{
+    ConfigError, Configuring, Duplex, LinkError, MdioTimeout, MiiError, MiiMode, MiimRead,
+    MiimWrite, Running, Speed, StopTimeout, config::ChecksumOffload, link, mii, poll, ral,
+};
+
+/// Returns the MDIO clock range (`CR`) for the CSR clock frequency.
+///
+/// Each range divides the CSR clock so that the MDIO clock does not
+/// exceed 2.5MHz.
+fn csr_clock_range(csr_clock_hz: u32) -> Result<u32, ConfigError> {
+    const MHZ: u32 = 1_000_000;
+    // (Upper bound of the frequency range, CR)
+    const RANGES: [(u32, u32); 8] = [
+        (35 * MHZ, 2),
+        (60 * MHZ, 3),
+        (100 * MHZ, 0),
+        (150 * MHZ, 1),
+        (250 * MHZ, 4),
+        (300 * MHZ, 5),
+        (500 * MHZ, 6),
+        (800 * MHZ, 7),
+    ];
+    if csr_clock_hz < 20 * MHZ {
+        return Err(ConfigError::SourceClock(csr_clock_hz));
+    }
+    RANGES
+        .iter()
+        .find(|(upper, _)| csr_clock_hz < *upper)
+        .map(|&(_, cr)| cr)
+        .ok_or(ConfigError::SourceClock(csr_clock_hz))
+}
+
+/// Returns the MTL queue size field for a FIFO size field.
+///
+/// The FIFO has `128 << fifo_size` bytes. The queue has `(queue_size + 1) * 256`
+/// bytes.
+const fn queue_size(fifo_size: u32) -> u32 {
+    ((128_u32 << fifo_size) / 256).saturating_sub(1)
+}
+
+/// An ENET_QOS MAC and related functions.
+///
+/// The driver's state `S` is either [`Configuring`] or [`Running`]. A new
+/// driver is configuring. Once your driver is configured, use
+/// [`enable_mac`](EnetQos::enable_mac) to enable the transmit and receive
+/// datapaths. To change the configuration, use
+/// [`disable_mac`](EnetQos::disable_mac).
+///
+/// The MDIO interface is always enabled. Use [`MiimRead`] and [`MiimWrite`]
+/// to manage your PHY, for instance with a driver from the
+/// [`phy`](crate::phy) module.
+///
+/// By default, the MAC checks and inserts IP and protocol checksums.
+pub struct EnetQos<S> {
+    qos: ral::enet_qos::ENET_QOS,
+    tx_ring: IoSlices<'static>,
+    rx_ring: IoSlices<'static>,
+    checksum: ChecksumOffload,
+    /// The MDIO clock range.
+    mdio_clock: u32,
+    mdio_timeout: MdioTimeout,
+    state: PhantomData<S>,
+}
+
+impl EnetQos<Configuring> {
+    /// Create and initialize an ENET_QOS driver.
+    ///
+    /// This resets and initializes the ENET_QOS IP block. However, the MAC
+    /// is off when the driver is returned. By default, the MAC uses a 100Mbit/s,
+    /// full-duplex link.
+    ///
+    /// `csr_clock_hz` is the frequency of the ENET_QOS bus clock. The driver
+    /// divides this clock for MDIO transfers. Returns an error if the
+    /// frequency is less than 20MHz, or at least 800MHz. Returns an error if
+    /// the reset does not finish, usually because the interface clocks are off.
+    pub fn new(
+        qos: ral::enet_qos::ENET_QOS,
+        tx_ring: IoSlices<'static>,
+        rx_ring: IoSlices<'static>,
+        csr_clock_hz: u32,
+        mac: &[u8; 6],
+    ) -> Result<Self, ConfigError> {
+        let mdio_clock = csr_clock_range(csr_clock_hz)?;
+
+        // Reset the DMA, MTL, and MAC. This needs the interface clocks.
+        ral::modify_reg!(ral::enet_qos, qos, DMA_MODE, SWR: 1);
+        if !poll(|| ral::read_reg!(ral::enet_qos, qos, DMA_MODE, SWR == 0)) {
+            return Err(ConfigError::ResetTimeout);
+        }
+
+        // Aligned bursts of up to 16 beats.
+        ral::write_reg!(ral::enet_qos, qos, DMA_SYSBUS_MODE,
+            AAL: 1,
+            BLEN16: 1,
+            BLEN8: 1,
+            BLEN4: 1,
+        );
+        // Descriptors are contiguous in their rings.
+        ral::write_reg!(ral::enet_qos, qos, DMA_CH0_CONTROL, DSL: 0, PBLX8: 0);
+        ral::write_reg!(ral::enet_qos, qos, DMA_CH0_TX_CONTROL,
+            TXPBL: 8,
+            // Fetch the next frame's descriptor while sending this frame.
+            OSF: 1,
+        );
+        // The receive buffer size includes four low bits of the register. The
+        // IoBuffers types require that the MTU is a multiple of 16.
+        debug_assert!(rx_ring.mtu() != 0 && rx_ring.mtu() & 0xF == 0);
+        ral::write_reg!(ral::enet_qos, qos, DMA_CH0_RX_CONTROL,
+            RXPBL: 8,
+            RBSZ_13_Y: (rx_ring.mtu() >> 4) as u32,
+        );
+        ral::write_reg!(ral::enet_qos, qos, DMA_CH0_TXDESC_RING_LENGTH, TDRL: tx_ring.len() as u32 - 1);
+        ral::write_reg!(ral::enet_qos, qos, DMA_CH0_RXDESC_RING_LENGTH, RDRL: rx_ring.len() as u32 - 1);
+
+        // Give all of the FIFOs to the only queues. Store-and-forward is
+        // necessary for checksum insertion, and it lets the MTL drop
+        // frames with errors.
+        let tx_fifo = ral::read_reg!(ral::enet_qos, qos, MAC_HW_FEATURE1, TXFIFOSIZE);
+        let rx_fifo = ral::read_reg!(ral::enet_qos, qos, MAC_HW_FEATURE1, RXFIFOSIZE);
+        ral::write_reg!(ral::enet_qos, qos, MTL_TXQ0_OPERATION_MODE,
+            TQS: queue_size(tx_fifo),
+            TXQEN: 2,
+            TSF: 1,
+        );
+        ral::write_reg!(ral::enet_qos, qos, MTL_RXQ0_OPERATION_MODE,
+            RQS: queue_size(rx_fifo),
+            RSF: 1,
+        );
+        ral::write_reg!(ral::enet_qos, qos, MAC_RXQ_CTRL0, RXQ0EN: 2);
+
+        write_mac(&qos, mac);
+        // Perfect filtering of unicast frames. Accept broadcast frames, and
+        // reject multicast frames.
+        ral::write_reg!(ral::enet_qos, qos, MAC_PACKET_FILTER, 0);
+        // React to pause frames by pausing the transmit path.
+        ral::write_reg!(ral::enet_qos, qos, MAC_RX_FLOW_CTRL, RFE: 1);
+
+        let checksum = ChecksumOffload { rx: true, tx: true };
+        ral::write_reg!(ral::enet_qos, qos, MAC_CONFIGURATION,
+            // Check the IP and protocol checksums of received frames.
+            IPC: checksum.rx as u32,
+            // Drop the CRC, and any padding, when supplying frames to our
+            // software.
+            CST: 1,
+            ACS: 1,
+        );
+        write_link(&qos, Speed::M100, Duplex::Full);
+
+        Ok(Self {
+            qos,
+            tx_ring,
+            rx_ring,
+            checksum,
+            mdio_clock,
+            mdio_timeout: MdioTimeout::default(),
+            state: PhantomData,
+        })
+    }
+
+    /// Configure the MAC for a link.
+    ///
+    /// `speed` and `duplex` describe the link, typically resolved by the PHY
+    /// through auto-negotiation. The MAC only checks `mode`, since the IOMUXC
+    /// GPR selects the interface.
+    ///
+    /// Returns an error if the MAC cannot support the link. In this case,
+    /// the MAC's configuration does not change.
+    pub fn set_link(
+        &mut self,
+        mode: MiiMode,
+        speed: Speed,
+        duplex: Duplex,
+    ) -> Result<(), LinkError> {
+        link::validate(true, mode, speed, duplex)?;
+        write_link(&self.qos, speed, duplex);
+        Ok(())
+    }
+
+    /// Check (`true`) the IP and protocol checksums of received frames in
+    /// hardware, or let smoltcp check them (`false`).
+    ///
+    /// When enabled, the driver drops frames with bad checksums. By default,
+    /// the hardware checks the checksums.
+    pub fn enable_rx_checksum_offload(&mut self, enable: bool) {
+        self.checksum.rx = enable;
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_CONFIGURATION, IPC: enable as u32);
+    }
+
+    /// Insert (`true`) the IP and protocol checksums of transmitted frames
+    /// in hardware, or let smoltcp compute them (`false`).
+    ///
+    /// By default, the hardware inserts the checksums.
+    pub fn enable_tx_checksum_offload(&mut self, enable: bool) {
+        self.checksum.tx = enable;
+    }
+
+    /// Enable the MAC.
+    ///
+    /// Once enabled, the MAC sends and receives frames. Make sure that
+    /// you've completed the driver configuration before enabling the MAC.
+    pub fn enable_mac(mut self) -> EnetQos<Running> {
+        self.start();
+        self.into_state()
+    }
+}
+
+impl EnetQos<Running> {
+    /// Disable the MAC.
+    ///
+    /// This finishes sending the current frame, then stops the MAC. Frames
+    /// that were waiting to be sent, and received frames that you have not
+    /// yet read, are dropped.
+    ///
+    /// Returns an error if the queues do not drain in time. The MAC is
+    /// disabled, but the driver does not take back the transmit ring.
+    #[expect(
+        clippy::result_large_err,
+        reason = "the error holds the driver, just like the success"
+    )]
+    pub fn disable_mac(
+        mut self,
+    ) -> Result<EnetQos<Configuring>, StopTimeout<EnetQos<Configuring>>> {
+        match self.stop() {
+            Ok(()) => Ok(self.into_state()),
+            Err(()) => Err(StopTimeout(self.into_state())),
+        }
+    }
+
+    /// Reconfigure the MAC for a new link.
+    ///
+    /// This stops the MAC like [`disable_mac`](Self::disable_mac), configures
+    /// the link like [`set_link`](EnetQos::set_link), then enables the MAC
+    /// again. Frames that were waiting to be sent or received are dropped.
+    ///
+    /// Returns an error if the MAC cannot support the link. In this case,
+    /// the MAC keeps running with its previous configuration. Returns
+    /// [`LinkError::Timeout`] if the queues do not drain in time. In this
+    /// case, the MAC is disabled.
+    pub fn relink(&mut self, mode: MiiMode, speed: Speed, duplex: Duplex) -> Result<(), LinkError> {
+        link::validate(true, mode, speed, duplex)?;
+        self.stop().map_err(|()| LinkError::Timeout)?;
+        write_link(&self.qos, speed, duplex);
+        self.start();
+        Ok(())
+    }
+
+    /// Stop the DMA and the MAC, then take back all transmit descriptors.
+    ///
+    /// If a queue does not drain in time, the DMA may still use the
+    /// descriptors, so this leaves the rings alone and returns an error.
+    fn stop(&mut self) -> Result<(), ()> {
+        // Finish the frame being sent, then stop the transmitter.
+        ral::modify_reg!(ral::enet_qos, self.qos, DMA_CH0_TX_CONTROL, ST: 0);
+        let mut drained = poll(|| {
+            let (trcsts, txqsts) =
+                ral::read_reg!(ral::enet_qos, self.qos, MTL_TXQ0_DEBUG, TRCSTS, TXQSTS);
+            // TRCSTS is 1 while the MAC reads a frame from the queue.
+            trcsts != 1 && txqsts == 0
+        });
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_CONFIGURATION, TE: 0, RE: 0);
+
+        // Let the DMA drain the receive queue, then stop it.
+        drained &= poll(|| {
+            let (prxq, rxqsts) =
+                ral::read_reg!(ral::enet_qos, self.qos, MTL_RXQ0_DEBUG, PRXQ, RXQSTS);
+            prxq == 0 && rxqsts == 0
+        });
+        ral::modify_reg!(ral::enet_qos, self.qos, DMA_CH0_RX_CONTROL, SR: 0);
+
+        if !drained {
+            return Err(());
+        }
+        // Starting the MAC again rewinds the receive ring.
+        self.tx_ring.rewind_transmit();
+        Ok(())
+    }
+}
+
+impl<S> EnetQos<S> {
+    fn into_state<T>(self) -> EnetQos<T> {
+        EnetQos {
+            qos: self.qos,
+            tx_ring: self.tx_ring,
+            rx_ring: self.rx_ring,
+            checksum: self.checksum,
+            mdio_clock: self.mdio_clock,
+            mdio_timeout: self.mdio_timeout,
+            state: PhantomData,
+        }
+    }
+
+    /// Point the DMA at the start of each ring, then start the DMA and the MAC.
+    fn start(&mut self) {
+        // Drop received frames, and give all receive descriptors to the DMA.
+        self.rx_ring.rewind_receive();
+        // Writing the list addresses, while the DMA is stopped, resets the
+        // DMA's position in each ring.
+        ral::write_reg!(
+            ral::enet_qos,
+            self.qos,
+            DMA_CH0_TXDESC_LIST_ADDRESS,
+            self.tx_ring.as_ptr() as u32
+        );
+        ral::write_reg!(
+            ral::enet_qos,
+            self.qos,
+            DMA_CH0_RXDESC_LIST_ADDRESS,
+            self.rx_ring.as_ptr() as u32
+        );
+        // There's nothing to send. All receive descriptors are available.
+        ral::write_reg!(
+            ral::enet_qos,
+            self.qos,
+            DMA_CH0_TXDESC_TAIL_POINTER,
+            self.tx_ring.next_address()
+        );
+        ral::write_reg!(
+            ral::enet_qos,
+            self.qos,
+            DMA_CH0_RXDESC_TAIL_POINTER,
+            self.rx_ring.end_address()
+        );
+
+        ral::modify_reg!(ral::enet_qos, self.qos, DMA_CH0_TX_CONTROL, ST: 1);
+        ral::modify_reg!(ral::enet_qos, self.qos, DMA_CH0_RX_CONTROL, SR: 1);
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_CONFIGURATION, TE: 1, RE: 1);
+    }
+
+    /// Returns the MAC address.
+    pub fn mac_address(&self) -> [u8; 6] {
+        let high = ral::read_reg!(ral::enet_qos, self.qos, MAC_ADDRESS0_HIGH, ADDRHI);
+        let low = ral::read_reg!(ral::enet_qos, self.qos, MAC_ADDRESS0_LOW);
+        let [a, b, c, d] = low.to_le_bytes();
+        let [e, f, _, _] = high.to_le_bytes();
+        [a, b, c, d, e, f]
+    }
+
+    /// Set the MAC address.
+    ///
+    /// The MAC uses this address to filter received frames. It does not
+    /// insert this address into transmitted frames; smoltcp does that. Make
+    /// sure to also update your smoltcp `Interface` with the new address.
+    pub fn set_mac_address(&mut self, mac: &[u8; 6]) {
+        write_mac(&self.qos, mac);
+    }
+
+    /// Enable (`true`) or disable (`false`) promiscuous mode.
+    ///
+    /// When enabled, the MAC receives all frames, regardless of their
+    /// destination address. By default, promiscuous mode is disabled.
+    ///
+    /// You may call this while the MAC is enabled.
+    #[inline]
+    pub fn enable_promiscuous_mode(&mut self, enable: bool) {
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_PACKET_FILTER, PR: enable as u32);
+    }
+
+    /// Enable (`true`) or disable (`false`) broadcast frame rejection.
+    ///
+    /// When enabled, the MAC drops all frames sent to the broadcast
+    /// address, unless it's in promiscuous mode. Note that IPv4 needs
+    /// broadcast frames for ARP. By default, the MAC accepts broadcast
+    /// frames.
+    ///
+    /// You may call this while the MAC is enabled.
+    #[inline]
+    pub fn enable_broadcast_reject(&mut self, enable: bool) {
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_PACKET_FILTER, DBF: enable as u32);
+    }
+
+    /// Accept (`true`) or reject (`false`) all multicast frames.
+    ///
+    /// The driver does not filter multicast groups, so accept all multicast
+    /// frames to receive any of them. By default, the MAC rejects multicast
+    /// frames.
+    ///
+    /// You may call this while the MAC is enabled.
+    #[inline]
+    pub fn accept_all_multicast(&mut self, accept: bool) {
+        ral::modify_reg!(ral::enet_qos, self.qos, MAC_PACKET_FILTER, PM: accept as u32);
+    }
+
+    /// Set how long MDIO transfers wait before returning [`MiiError::Timeout`].
+    #[inline]
+    pub fn set_mdio_timeout(&mut self, timeout: MdioTimeout) {
+        self.mdio_timeout = timeout;
+    }
+
+    /// Perform a Clause 22 MDIO transfer, returning the data.
+    ///
+    /// Writes `data` when it's `Some`, and reads otherwise.
+    fn mdio_transfer(
+        &mut self,
+        phy_addr: u8,
+        reg_addr: u8,
+        data: Option<u16>,
+    ) -> Result<u16, MiiError> {
+        let qos = &self.qos;
+        // A transfer that previously timed out might still be running.
+        self.mdio_timeout
+            .wait(|| ral::read_reg!(ral::enet_qos, qos, MAC_MDIO_ADDRESS, GB == 0))?;
+        if let Some(data) = data {
+            ral::write_reg!(ral::enet_qos, qos, MAC_MDIO_DATA, GD: data as u32);
+        }
+        ral::write_reg!(ral::enet_qos, qos, MAC_MDIO_ADDRESS,
+            PA: (phy_addr & 0x1F) as u32,
+            RDA: (reg_addr & 0x1F) as u32,
+            CR: self.mdio_clock,
+            // 0b11 reads, and 0b01 writes.
+            GOC_1: data.is_none() as u32,
+            GOC_0: 1,
+            GB: 1,
+        );
+        self.mdio_timeout
+            .wait(|| ral::read_reg!(ral::enet_qos, qos, MAC_MDIO_ADDRESS, GB == 0))?;
+        Ok(ral::read_reg!(ral::enet_qos, qos, MAC_MDIO_DATA, GD) as u16)
+    }
+}
+
+/// Configure the MAC for a validated link.
+fn write_link(qos: &ral::enet_qos::RegisterBlock, speed: Speed, duplex: Duplex) {
+    // PS selects the 10/100 port. FES selects 100Mbit/s on that port.
+    ral::modify_reg!(ral::enet_qos, qos, MAC_CONFIGURATION,
+        PS: (speed != Speed::M1000) as u32,
+        FES: (speed == Speed::M100) as u32,
+        DM: (duplex == Duplex::Full) as u32,
+    );
+}
+
+/// Commit the MAC address to the address registers.
+fn write_mac(qos: &ral::enet_qos::RegisterBlock, mac: &[u8; 6]) {
+    // The MAC updates the address when we write the low register.
+    ral::write_reg!(ral::enet_qos, qos, MAC_ADDRESS0_HIGH,
+        ADDRHI: u16::from_le_bytes([mac[4], mac[5]]) as u32
+    );
+    ral::write_reg!(
+        ral::enet_qos,
+        qos,
+        MAC_ADDRESS0_LOW,
+        u32::from_le_bytes([mac[0], mac[1], mac[2], mac[3]])
+    );
+}
+
+impl<S> MiimRead for EnetQos<S> {
+    type Error = MiiError;
+
+    fn read(&mut self, phy_addr: u8, reg_addr: u8) -> Result<u16, Self::Error> {
+        mii::clause22_data(self.mdio_transfer(phy_addr, reg_addr, None)?)
+    }
+}
+
+impl<S> MiimWrite for EnetQos<S> {
+    type Error = MiiError;
+
+    fn write(&mut self, phy_addr: u8, reg_addr: u8, data: u16) -> Result<(), Self::Error> {
+        self.mdio_transfer(phy_addr, reg_addr, Some(data))?;
+        Ok(())
+    }
+}
+
+impl smoltcp::phy::Device for EnetQos<Running> {
+    type RxToken<'a> = bd::RxToken<'a>;
+    type TxToken<'a> = bd::TxToken<'a>;
+
+    fn receive(
+        &mut self,
+        _: smoltcp::time::Instant,
+    ) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        let tx = self.tx_ring.next_transmit(&self.qos, self.checksum.tx)?;
+        let rx = self.rx_ring.next_receive(&self.qos, self.checksum.rx)?;
+        Some((rx, tx))
+    }
+
+    fn transmit(&mut self, _: smoltcp::time::Instant) -> Option<Self::TxToken<'_>> {
+        self.tx_ring.next_transmit(&self.qos, self.checksum.tx)
+    }
+
+    fn capabilities(&self) -> smoltcp::phy::DeviceCapabilities {
+        let mtu = self.tx_ring.mtu().min(self.rx_ring.mtu());
+
+        let mut caps = smoltcp::phy::DeviceCapabilities::default();
+        caps.medium = smoltcp::phy::Medium::Ethernet;
+        caps.max_transmission_unit = mtu;
+        caps.max_burst_size = Some(self.tx_ring.len().min(self.rx_ring.len()));
+
+        // smoltcp handles the checksums that the hardware does not.
+        let checksum = match (self.checksum.rx, self.checksum.tx) {
+            (true, true) => smoltcp::phy::Checksum::None,
+            (true, false) => smoltcp::phy::Checksum::Tx,
+            (false, true) => smoltcp::phy::Checksum::Rx,
+            (false, false) => smoltcp::phy::Checksum::Both,
+        };
+        caps.checksum.ipv4 = checksum;
+        caps.checksum.udp = checksum;
+        caps.checksum.tcp = checksum;
+        caps.checksum.icmpv4 = checksum;
+
+        caps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csr_clock_range, queue_size};
+    use crate::ConfigError;
+
+    #[test]
+    fn csr_clock() {
+        assert_eq!(csr_clock_range(20_000_000), Ok(2));
+        assert_eq!(csr_clock_range(50_000_000), Ok(3));
+        assert_eq!(csr_clock_range(60_000_000), Ok(0));
+        assert_eq!(csr_clock_range(125_000_000), Ok(1));
+        assert_eq!(csr_clock_range(240_000_000), Ok(4));
+        assert_eq!(csr_clock_range(799_999_999), Ok(7));
+        assert_eq!(
+            csr_clock_range(19_999_999),
+            Err(ConfigError::SourceClock(19_999_999))
+        );
+        assert_eq!(
+            csr_clock_range(800_000_000),
+            Err(ConfigError::SourceClock(800_000_000))
+        );
+    }
+
+    #[test]
+    fn queue_sizes() {
+        // 128 bytes to 256 bytes, then 2KiB and 8KiB.
+        assert_eq!(queue_size(0), 0);
+        assert_eq!(queue_size(1), 0);
+        assert_eq!(queue_size(4), 7);
+        assert_eq!(queue_size(6), 31);
+    }
+}
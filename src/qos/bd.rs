@@ -0,0 +1,437 @@
+//! ENET_QOS DMA descriptors, rings, and buffers.
+//!
+//! Transmit and receive descriptors are four words. Software prepares a
+//! descriptor in its "read" format, then gives it to the DMA. When the DMA is
+//! done, it overwrites the descriptor with its "write-back" format. These
+//! definitions come from the i.MX RT 1170 reference manual, revision 2.
+
+#![expect(
+    clippy::new_without_default,
+    reason = "default() isn't const, useless for static init"
+)]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::ral;
+
+/// The number of descriptors must fit the ring length registers.
+const MAX_DESCRIPTORS: usize = 1 << 10;
+/// The buffer length must fit the descriptors' length fields.
+const MAX_MTU: usize = 1 << 14;
+
+pub const TDES2_BUFFER1_LENGTH: u32 = 0x3FFF;
+pub const TDES3_OWN: u32 = 1 << 31;
+pub const TDES3_FIRST: u32 = 1 << 29;
+pub const TDES3_LAST: u32 = 1 << 28;
+/// Insert the IP header and protocol checksums.
+pub const TDES3_CHECKSUM_FULL: u32 = 0b11 << 16;
+pub const TDES3_FRAME_LENGTH: u32 = 0x7FFF;
+
+pub const RDES1_IP_PAYLOAD_ERROR: u32 = 1 << 7;
+pub const RDES1_IP_HEADER_ERROR: u32 = 1 << 3;
+pub const RDES3_OWN: u32 = 1 << 31;
+/// In the read format, interrupt on completion.
+pub const RDES3_IOC: u32 = 1 << 30;
+/// In the write-back format, this is a context descriptor.
+pub const RDES3_CONTEXT: u32 = 1 << 30;
+pub const RDES3_FIRST: u32 = 1 << 29;
+pub const RDES3_LAST: u32 = 1 << 28;
+/// In the write-back format, RDES1 is valid.
+pub const RDES3_RDES1_VALID: u32 = 1 << 26;
+/// In the read format, buffer 1 is valid.
+pub const RDES3_BUFFER1_VALID: u32 = 1 << 24;
+pub const RDES3_ERROR_SUMMARY: u32 = 1 << 15;
+pub const RDES3_PACKET_LENGTH: u32 = 0x7FFF;
+
+/// A transmit or receive descriptor.
+#[repr(C)]
+pub struct Descriptor {
+    des: [AtomicU32; 4],
+}
+
+impl Descriptor {
+    const fn zero() -> Self {
+        Self {
+            des: [const { AtomicU32::new(0) }; 4],
+        }
+    }
+
+    /// Give the descriptor to the DMA.
+    ///
+    /// The last word holds the ownership bit, so it's written last.
+    fn store(&self, words: [u32; 4]) {
+        for (des, word) in self.des[..3].iter().zip(words) {
+            des.store(word, Ordering::Relaxed);
+        }
+        self.des[3].store(words[3], Ordering::SeqCst);
+    }
+
+    fn is_owned(&self) -> bool {
+        self.des[3].load(Ordering::SeqCst) & TDES3_OWN != 0
+    }
+}
+
+/// Returns the read format of a transmit descriptor.
+const fn transmit_descriptor(buffer: u32, len: usize, checksum: bool) -> [u32; 4] {
+    let checksum = if checksum { TDES3_CHECKSUM_FULL } else { 0 };
+    [
+        buffer,
+        0,
+        len as u32 & TDES2_BUFFER1_LENGTH,
+        TDES3_OWN | TDES3_FIRST | TDES3_LAST | checksum | (len as u32 & TDES3_FRAME_LENGTH),
+    ]
+}
+
+/// Returns the read format of a receive descriptor.
+const fn receive_descriptor(buffer: u32) -> [u32; 4] {
+    [buffer, 0, 0, RDES3_OWN | RDES3_IOC | RDES3_BUFFER1_VALID]
+}
+
+/// Returns the length of a received frame from its write-back descriptor.
+///
+/// Returns `None` if the frame has errors, or if it does not fit in one
+/// buffer. When `checksum` is set, frames with bad IP or protocol checksums
+/// are errors.
+fn received_length(rdes1: u32, rdes3: u32, checksum: bool) -> Option<usize> {
+    let whole = RDES3_FIRST | RDES3_LAST;
+    if rdes3 & (whole | RDES3_CONTEXT | RDES3_ERROR_SUMMARY) != whole {
+        return None;
+    }
+    let checksum_errors = RDES1_IP_HEADER_ERROR | RDES1_IP_PAYLOAD_ERROR;
+    if checksum && rdes3 & RDES3_RDES1_VALID != 0 && rdes1 & checksum_errors != 0 {
+        return None;
+    }
+    Some((rdes3 & RDES3_PACKET_LENGTH) as usize)
+}
+
+#[repr(align(64))]
+struct DescriptorRing<const N: usize>([Descriptor; N]);
+
+#[repr(align(64))]
+struct DataBuffers<const COUNT: usize, const MTU: usize>([[u8; MTU]; COUNT]);
+
+/// Buffers for Ethernet frames.
+///
+/// `COUNT` is how many `MTU`-sized buffers are available to receive and
+/// transmit. By default, `MTU` is 1536 bytes. `MTU` must be a multiple of 16,
+/// and `COUNT` cannot exceed 1024.
+///
+/// Each buffer has its own DMA descriptor.
+pub struct IoBuffers<const COUNT: usize, const MTU: usize = 1536> {
+    ring: DescriptorRing<COUNT>,
+    buffers: DataBuffers<COUNT, MTU>,
+}
+
+/// Buffers in the transmit path.
+pub type TransmitBuffers<const COUNT: usize, const MTU: usize = 1536> = IoBuffers<COUNT, MTU>;
+/// Buffers in the receive path.
+pub type ReceiveBuffers<const COUNT: usize, const MTU: usize = 1536> = IoBuffers<COUNT, MTU>;
+
+impl<const COUNT: usize, const MTU: usize> IoBuffers<COUNT, MTU> {
+    /// Allocate space for the buffers and their descriptors.
+    pub const fn new() -> Self {
+        assert!(MTU.is_multiple_of(16) && MTU < MAX_MTU);
+        assert!(COUNT > 0 && COUNT <= MAX_DESCRIPTORS);
+        Self {
+            ring: DescriptorRing([const { Descriptor::zero() }; COUNT]),
+            buffers: DataBuffers([[0; MTU]; COUNT]),
+        }
+    }
+
+    /// Take the buffers and represent them as slices.
+    ///
+    /// The driver prepares the descriptors when it uses the slices.
+    pub fn take(&'static mut self) -> IoSlices<'static> {
+        IoSlices {
+            ring: &mut self.ring.0,
+            buffers: self.buffers.0.as_flattened_mut(),
+            mtu: MTU,
+            index: 0,
+        }
+    }
+}
+
+/// Tracks buffers and a ring of descriptors.
+pub struct IoSlices<'a> {
+    ring: &'a mut [Descriptor],
+    /// All buffers, one after the other.
+    buffers: &'a mut [u8],
+    mtu: usize,
+    index: usize,
+}
+
+impl IoSlices<'_> {
+    pub(crate) fn as_ptr(&self) -> *const Descriptor {
+        self.ring.as_ptr()
+    }
+    pub(crate) fn mtu(&self) -> usize {
+        self.mtu
+    }
+    pub(crate) fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns the DMA address of the descriptor at `index`.
+    ///
+    /// `index` may be the ring's length, which is the end of the ring.
+    fn descriptor_address(&self, index: usize) -> u32 {
+        self.ring.as_ptr().wrapping_add(index) as u32
+    }
+
+    /// Returns the DMA address of the next descriptor for software.
+    pub(crate) fn next_address(&self) -> u32 {
+        self.descriptor_address(self.index)
+    }
+
+    /// Returns the DMA address of the end of the ring.
+    pub(crate) fn end_address(&self) -> u32 {
+        self.descriptor_address(self.ring.len())
+    }
+
+    fn buffer_address(&self, index: usize) -> u32 {
+        self.buffers[index * self.mtu..].as_ptr() as u32
+    }
+
+    /// Take back all transmit descriptors from the DMA, and restart at the
+    /// first descriptor.
+    ///
+    /// Frames that the DMA has not sent are dropped. Only call this once the
+    /// DMA is idle.
+    pub(crate) fn rewind_transmit(&mut self) {
+        for descriptor in self.ring.iter() {
+            descriptor.store([0; 4]);
+        }
+        self.index = 0;
+    }
+
+    /// Give all receive descriptors to the DMA, and restart at the first
+    /// descriptor.
+    ///
+    /// Received frames are dropped. Only call this once the DMA is idle.
+    pub(crate) fn rewind_receive(&mut self) {
+        for (index, descriptor) in self.ring.iter().enumerate() {
+            descriptor.store(receive_descriptor(self.buffer_address(index)));
+        }
+        self.index = 0;
+    }
+
+    /// Returns a token for the next transmit descriptor, if the DMA is done
+    /// with it.
+    pub(crate) fn next_transmit<'a>(
+        &'a mut self,
+        qos: &'a ral::enet_qos::RegisterBlock,
+        checksum: bool,
+    ) -> Option<TxToken<'a>> {
+        if self.ring[self.index].is_owned() {
+            return None;
+        }
+        let next = (self.index + 1) % self.ring.len();
+        Some(TxToken {
+            tail: self.descriptor_address(next),
+            address: self.buffer_address(self.index),
+            descriptor: &self.ring[self.index],
+            buffer: &mut self.buffers[self.index * self.mtu..][..self.mtu],
+            index: &mut self.index,
+            next,
+            qos,
+            checksum,
+        })
+    }
+
+    /// Returns a token for the next received frame.
+    ///
+    /// Frames with errors are dropped, and their descriptors are given back
+    /// to the DMA.
+    pub(crate) fn next_receive<'a>(
+        &'a mut self,
+        qos: &'a ral::enet_qos::RegisterBlock,
+        checksum: bool,
+    ) -> Option<RxToken<'a>> {
+        for _ in 0..self.ring.len() {
+            let descriptor = &self.ring[self.index];
+            if descriptor.is_owned() {
+                return None;
+            }
+            let rdes1 = descriptor.des[1].load(Ordering::Relaxed);
+            let rdes3 = descriptor.des[3].load(Ordering::Relaxed);
+            let next = (self.index + 1) % self.ring.len();
+            match received_length(rdes1, rdes3, checksum) {
+                Some(len) if len <= self.mtu => {
+                    return Some(RxToken {
+                        tail: self.end_address(),
+                        address: self.buffer_address(self.index),
+                        descriptor,
+                        buffer: &mut self.buffers[self.index * self.mtu..][..len],
+                        index: &mut self.index,
+                        next,
+                        qos,
+                    });
+                }
+                _ => {
+                    descriptor.store(receive_descriptor(self.buffer_address(self.index)));
+                    self.index = next;
+                    write_rx_tail(qos, self.end_address());
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Resume the receive DMA, if it ran out of descriptors.
+fn write_rx_tail(qos: &ral::enet_qos::RegisterBlock, tail: u32) {
+    ral::write_reg!(ral::enet_qos, qos, DMA_CH0_RXDESC_TAIL_POINTER, tail);
+}
+
+pub struct TxToken<'a> {
+    descriptor: &'a Descriptor,
+    buffer: &'a mut [u8],
+    /// The buffer's DMA address.
+    address: u32,
+    index: &'a mut usize,
+    next: usize,
+    /// The address of the next descriptor.
+    tail: u32,
+    qos: &'a ral::enet_qos::RegisterBlock,
+    checksum: bool,
+}
+
+impl smoltcp::phy::TxToken for TxToken<'_> {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        assert!(len <= self.buffer.len());
+        let result = f(&mut self.buffer[..len]);
+
+        self.descriptor
+            .store(transmit_descriptor(self.address, len, self.checksum));
+        *self.index = self.next;
+        // The DMA sends descriptors up to, but not including, the tail.
+        ral::write_reg!(
+            ral::enet_qos,
+            self.qos,
+            DMA_CH0_TXDESC_TAIL_POINTER,
+            self.tail
+        );
+        result
+    }
+}
+
+pub struct RxToken<'a> {
+    descriptor: &'a Descriptor,
+    buffer: &'a mut [u8],
+    /// The buffer's DMA address.
+    address: u32,
+    index: &'a mut usize,
+    next: usize,
+    /// The end of the ring.
+    tail: u32,
+    qos: &'a ral::enet_qos::RegisterBlock,
+}
+
+impl RxToken<'_> {
+    /// Consume the received frame, then give the descriptor back to the DMA.
+    pub(crate) fn consume_mut<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let result = f(self.buffer);
+        // The write-back format replaced the buffer address.
+        self.descriptor.store(receive_descriptor(self.address));
+        *self.index = self.next;
+        write_rx_tail(self.qos, self.tail);
+        result
+    }
+}
+
+impl smoltcp::phy::RxToken for RxToken<'_> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.consume_mut(|buffer| f(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Descriptor, RDES1_IP_HEADER_ERROR, RDES3_CONTEXT, RDES3_ERROR_SUMMARY, RDES3_FIRST,
+        RDES3_LAST, RDES3_OWN, RDES3_RDES1_VALID, ReceiveBuffers, TransmitBuffers,
+        receive_descriptor, received_length, transmit_descriptor,
+    };
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn layout() {
+        assert_eq!(core::mem::size_of::<Descriptor>(), 16);
+        let buffers = Box::leak(Box::new(TransmitBuffers::<3, 64>::new()));
+        let slices = buffers.take();
+        assert_eq!(slices.as_ptr() as usize % 64, 0);
+        assert_eq!(slices.end_address() - slices.next_address(), 3 * 16);
+        assert_eq!(slices.buffer_address(2) - slices.buffer_address(0), 2 * 64);
+    }
+
+    #[test]
+    fn transmit() {
+        assert_eq!(
+            transmit_descriptor(0x2020_0000, 1514, true),
+            [0x2020_0000, 0, 1514, 0xB003_05EA]
+        );
+        assert_eq!(
+            transmit_descriptor(0x2020_0000, 60, false),
+            [0x2020_0000, 0, 60, 0xB000_003C]
+        );
+    }
+
+    #[test]
+    fn receive() {
+        assert_eq!(
+            receive_descriptor(0x2020_0000),
+            [0x2020_0000, 0, 0, 0xC100_0000]
+        );
+
+        let whole = RDES3_FIRST | RDES3_LAST;
+        assert_eq!(received_length(0, whole | 1514, true), Some(1514));
+        assert_eq!(received_length(0, RDES3_FIRST | 1536, true), None);
+        assert_eq!(received_length(0, RDES3_LAST | 20, true), None);
+        assert_eq!(
+            received_length(0, whole | RDES3_ERROR_SUMMARY | 64, true),
+            None
+        );
+        assert_eq!(received_length(0, whole | RDES3_CONTEXT, true), None);
+
+        // Checksum errors only matter if the MAC checks checksums.
+        let rdes3 = whole | RDES3_RDES1_VALID | 64;
+        assert_eq!(received_length(RDES1_IP_HEADER_ERROR, rdes3, true), None);
+        assert_eq!(
+            received_length(RDES1_IP_HEADER_ERROR, rdes3, false),
+            Some(64)
+        );
+    }
+
+    #[test]
+    fn rewind() {
+        let buffers = Box::leak(Box::new(ReceiveBuffers::<3, 64>::new()));
+        let mut slices = buffers.take();
+        slices.index = 2;
+        slices.rewind_receive();
+        assert_eq!(slices.index, 0);
+        for (index, descriptor) in slices.ring.iter().enumerate() {
+            assert_eq!(
+                descriptor.des[0].load(Ordering::Relaxed),
+                slices.buffer_address(index)
+            );
+            assert!(descriptor.is_owned());
+        }
+
+        slices.rewind_transmit();
+        assert!(
+            slices
+                .ring
+                .iter()
+                .all(|descriptor| { descriptor.des[3].load(Ordering::Relaxed) & RDES3_OWN == 0 })
+        );
+    }
+}
@@ -0,0 +1,394 @@
+//! ENET_1G transmit and receive queues.
+//!
+//! ENET_1G has three pairs of descriptor rings. Queue 0 uses the driver's
+//! rings. Attach rings to queues 1 and 2, then assign VLAN priorities to
+//! those queues. The MAC places received VLAN frames with those priorities
+//! into the queue's receive ring. All other frames go to queue 0.
+//!
+//! The smoltcp device receives from queues 2 and 1 before it receives from
+//! queue 0, and it transmits on queue 0. To transmit a frame on another
+//! queue, use [`Enet::transmit_on`].
+
+use crate::{
+    Configuring, Enet, ReceiveSlices, Running, RxReady, TransmitSlices, TxReady, as_enet_1g, bd,
+    poll, ral,
+};
+
+/// A pair of ENET_1G descriptor rings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Queue {
+    /// The driver's rings.
+    ///
+    /// This queue receives all frames that are not assigned to another
+    /// queue.
+    Q0,
+    /// The first class of traffic.
+    Q1,
+    /// The second class of traffic.
+    Q2,
+}
+
+impl Queue {
+    /// Returns the index of the queue's class registers, or `None` for
+    /// queue 0.
+    const fn class(self) -> Option<usize> {
+        match self {
+            Self::Q0 => None,
+            Self::Q1 => Some(0),
+            Self::Q2 => Some(1),
+        }
+    }
+}
+
+/// How the MAC chooses the next queue to transmit from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxScheme {
+    /// Queues 1 and 2 are shaped classes that take priority over queue 0.
+    ///
+    /// Each class may use up to half of the bandwidth. This is the default.
+    CreditBased,
+    /// Take turns transmitting from each queue.
+    RoundRobin,
+}
+
+/// An invalid queue configuration.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum QueueError {
+    /// The driver does not manage ENET_1G.
+    Unsupported,
+    /// Queue 0 always uses the driver's rings, and it receives all frames
+    /// that are not assigned to another queue.
+    DefaultQueue,
+    /// The queue already has rings.
+    Attached(Queue),
+    /// The receive buffer, in bytes, is smaller than the maximum frame length.
+    BufferSize(usize),
+    /// There are more than four priorities, or a priority is greater than 7.
+    Priorities,
+}
+
+/// Rings attached to a queue.
+struct Rings {
+    tx: TransmitSlices<'static>,
+    rx: ReceiveSlices<'static>,
+}
+
+/// The rings and receive classifications of queues 1 and 2.
+pub(crate) struct Queues {
+    rings: [Option<Rings>; 2],
+    /// The classifications, committed while the queue has rings.
+    rcmr: [u32; 2],
+}
+
+impl Queues {
+    pub(crate) const fn new() -> Self {
+        Self {
+            rings: [None, None],
+            rcmr: [0; 2],
+        }
+    }
+
+    /// Returns a token for the next frame received in queue 2, then in
+    /// queue 1.
+    pub(crate) fn next_receive<'a>(
+        &'a mut self,
+        enet: &'a ral::enet::RegisterBlock,
+    ) -> Option<bd::RxToken<'a>> {
+        let queues = [Queue::Q1, Queue::Q2];
+        for (rings, queue) in self.rings.iter_mut().zip(queues).rev() {
+            let Some(rings) = rings else { continue };
+            if let Some(token) = rings.rx.next_token(RxReady::on(enet, queue)) {
+                return Some(token);
+            }
+        }
+        None
+    }
+
+    /// Tell the DMA that the receive descriptors are available.
+    pub(crate) fn start(&self, enet: &ral::enet::RegisterBlock) {
+        if self.rings.iter().any(Option::is_some) {
+            // Safety: only ENET_1G has rings in these queues.
+            let enet = unsafe { as_enet_1g(enet) };
+            if self.rings[0].is_some() {
+                ral::write_reg!(ral::enet_1g, enet, RDAR1, RDAR: 1);
+            }
+            if self.rings[1].is_some() {
+                ral::write_reg!(ral::enet_1g, enet, RDAR2, RDAR: 1);
+            }
+        }
+    }
+
+    /// Wait for the DMA to go idle.
+    ///
+    /// Only call this once the MAC is disabled. Returns `false` if the DMA
+    /// did not go idle in time.
+    pub(crate) fn halt(&self, enet: &ral::enet::RegisterBlock) -> bool {
+        if self.rings.iter().all(Option::is_none) {
+            return true;
+        }
+        // Safety: only ENET_1G has rings in these queues.
+        let enet = unsafe { as_enet_1g(enet) };
+        poll(|| {
+            ral::read_reg!(ral::enet_1g, enet, TDAR1, TDAR == 0)
+                && ral::read_reg!(ral::enet_1g, enet, RDAR1, RDAR == 0)
+                && ral::read_reg!(ral::enet_1g, enet, TDAR2, TDAR == 0)
+                && ral::read_reg!(ral::enet_1g, enet, RDAR2, RDAR == 0)
+        })
+    }
+
+    /// Take back all descriptors, and restart at the start of each ring.
+    ///
+    /// Only call this once the DMA is idle, or reset.
+    pub(crate) fn rewind(&mut self) {
+        for rings in self.rings.iter_mut().flatten() {
+            rings.tx.rewind();
+            rings.rx.rewind();
+        }
+    }
+
+    /// Take the rings of queues 1 and 2.
+    ///
+    /// Only call this once the DMA is idle, or reset.
+    pub(crate) fn take_rings(
+        &mut self,
+    ) -> [Option<(TransmitSlices<'static>, ReceiveSlices<'static>)>; 2] {
+        self.rings
+            .each_mut()
+            .map(|rings| rings.take().map(|rings| (rings.tx, rings.rx)))
+    }
+}
+
+/// Tell the DMA that a queue has a frame to send.
+///
+/// # Safety
+///
+/// If `queue` is not queue 0, `enet` must be an ENET_1G instance.
+pub(crate) unsafe fn transmit_ready(enet: &ral::enet::RegisterBlock, queue: Queue) {
+    match queue {
+        Queue::Q0 => ral::write_reg!(ral::enet, enet, TDAR, TDAR: 1),
+        Queue::Q1 => {
+            // Safety: the caller ensures this is ENET_1G.
+            let enet = unsafe { as_enet_1g(enet) };
+            ral::write_reg!(ral::enet_1g, enet, TDAR1, TDAR: 1);
+        }
+        Queue::Q2 => {
+            // Safety: the caller ensures this is ENET_1G.
+            let enet = unsafe { as_enet_1g(enet) };
+            ral::write_reg!(ral::enet_1g, enet, TDAR2, TDAR: 1);
+        }
+    }
+}
+
+/// Tell the DMA that a queue has a free receive descriptor.
+///
+/// # Safety
+///
+/// If `queue` is not queue 0, `enet` must be an ENET_1G instance.
+pub(crate) unsafe fn receive_ready(enet: &ral::enet::RegisterBlock, queue: Queue) {
+    match queue {
+        Queue::Q0 => ral::write_reg!(ral::enet, enet, RDAR, RDAR: 1),
+        Queue::Q1 => {
+            // Safety: the caller ensures this is ENET_1G.
+            let enet = unsafe { as_enet_1g(enet) };
+            ral::write_reg!(ral::enet_1g, enet, RDAR1, RDAR: 1);
+        }
+        Queue::Q2 => {
+            // Safety: the caller ensures this is ENET_1G.
+            let enet = unsafe { as_enet_1g(enet) };
+            ral::write_reg!(ral::enet_1g, enet, RDAR2, RDAR: 1);
+        }
+    }
+}
+
+/// Returns the RCMR value that matches VLAN `priorities`.
+///
+/// An empty set of priorities disables matching.
+fn rcmr(priorities: &[u8]) -> Result<u32, QueueError> {
+    use ral::enet_1g::RCMR;
+
+    if priorities.len() > 4 || priorities.iter().any(|&priority| priority > 7) {
+        return Err(QueueError::Priorities);
+    }
+    let Some(&first) = priorities.first() else {
+        return Ok(0);
+    };
+    // Unused comparators repeat a priority. Otherwise, they'd match
+    // priority 0.
+    let compare = |index: usize| priorities.get(index).copied().unwrap_or(first) as u32;
+    Ok(compare(0) << RCMR::CMP0::offset
+        | compare(1) << RCMR::CMP1::offset
+        | compare(2) << RCMR::CMP2::offset
+        | compare(3) << RCMR::CMP3::offset
+        | RCMR::MATCHEN::mask)
+}
+
+impl Enet<Configuring> {
+    /// Attach transmit and receive rings to `queue`.
+    ///
+    /// The queue does not receive frames until you assign it
+    /// [priorities](Enet::set_queue_priorities). Each receive buffer must
+    /// hold a frame of the maximum frame length.
+    ///
+    /// Returns an error if the driver does not manage ENET_1G, if `queue`
+    /// is queue 0, if the queue already has rings, or if the receive buffers
+    /// are too small. In this case, the rings are dropped.
+    pub fn attach_queue(
+        &mut self,
+        queue: Queue,
+        tx_ring: TransmitSlices<'static>,
+        rx_ring: ReceiveSlices<'static>,
+    ) -> Result<(), QueueError> {
+        if !self.gigabit {
+            return Err(QueueError::Unsupported);
+        }
+        let class = queue.class().ok_or(QueueError::DefaultQueue)?;
+        if self.queues.rings[class].is_some() {
+            return Err(QueueError::Attached(queue));
+        }
+        let max_frame_length = ral::read_reg!(ral::enet, self.enet, RCR, MAX_FL) as usize;
+        if rx_ring.mtu() < max_frame_length {
+            return Err(QueueError::BufferSize(rx_ring.mtu()));
+        }
+
+        // Safety: the driver manages ENET_1G.
+        let enet = unsafe { as_enet_1g(&self.enet) };
+        let buffer_size = (rx_ring.mtu() >> 4) as u32;
+        let (tdsr, rdsr) = (tx_ring.as_ptr() as u32, rx_ring.as_ptr() as u32);
+        match queue {
+            Queue::Q1 => {
+                ral::write_reg!(ral::enet_1g, enet, MRBR1, R_BUF_SIZE: buffer_size);
+                ral::write_reg!(ral::enet_1g, enet, TDSR1, tdsr);
+                ral::write_reg!(ral::enet_1g, enet, RDSR1, rdsr);
+                // When the ring is full, drop its frames, so that they don't
+                // block the other queues.
+                ral::modify_reg!(ral::enet_1g, enet, QOS, RX_FLUSH1: 1);
+            }
+            Queue::Q2 => {
+                ral::write_reg!(ral::enet_1g, enet, MRBR2, R_BUF_SIZE: buffer_size);
+                ral::write_reg!(ral::enet_1g, enet, TDSR2, tdsr);
+                ral::write_reg!(ral::enet_1g, enet, RDSR2, rdsr);
+                ral::modify_reg!(ral::enet_1g, enet, QOS, RX_FLUSH2: 1);
+            }
+            Queue::Q0 => unreachable!(),
+        }
+        // Half of the bandwidth in the credit-based scheme.
+        ral::write_reg!(ral::enet_1g, enet, DMACFG[class], DMA_CLASS_EN: 1, IDLE_SLOPE: 0x200);
+        ral::write_reg!(ral::enet_1g, enet, RCMR[class], self.queues.rcmr[class]);
+
+        self.queues.rings[class] = Some(Rings {
+            tx: tx_ring,
+            rx: rx_ring,
+        });
+        Ok(())
+    }
+
+    /// Detach the rings from `queue`, and return them.
+    ///
+    /// Frames that were waiting to be sent or received are dropped. The
+    /// frames assigned to the queue go to queue 0. Returns `None` if the
+    /// queue does not have rings.
+    pub fn detach_queue(
+        &mut self,
+        queue: Queue,
+    ) -> Option<(TransmitSlices<'static>, ReceiveSlices<'static>)> {
+        let class = queue.class()?;
+        let rings = self.queues.rings[class].take()?;
+
+        // Safety: only ENET_1G has rings in these queues.
+        let enet = unsafe { as_enet_1g(&self.enet) };
+        ral::write_reg!(ral::enet_1g, enet, RCMR[class], 0);
+        ral::write_reg!(ral::enet_1g, enet, DMACFG[class], 0);
+        match queue {
+            Queue::Q1 => ral::modify_reg!(ral::enet_1g, enet, QOS, RX_FLUSH1: 0),
+            Queue::Q2 => ral::modify_reg!(ral::enet_1g, enet, QOS, RX_FLUSH2: 0),
+            Queue::Q0 => unreachable!(),
+        }
+        Some((rings.tx, rings.rx))
+    }
+
+    /// Select how the MAC chooses the next queue to transmit from.
+    ///
+    /// This has no effect unless the driver manages ENET_1G.
+    pub fn set_tx_scheme(&mut self, scheme: TxScheme) {
+        if self.gigabit {
+            // Safety: the driver manages ENET_1G.
+            let enet = unsafe { as_enet_1g(&self.enet) };
+            let scheme = match scheme {
+                TxScheme::CreditBased => ral::enet_1g::QOS::TX_SCHEME::RW::CREDIT,
+                TxScheme::RoundRobin => ral::enet_1g::QOS::TX_SCHEME::RW::RR,
+            };
+            ral::modify_reg!(ral::enet_1g, enet, QOS, TX_SCHEME: scheme);
+        }
+    }
+}
+
+impl<S> Enet<S> {
+    /// Receive VLAN frames with up to four `priorities` in `queue`.
+    ///
+    /// Priorities are the VLAN tag's priority code point, from 0 to 7. If
+    /// both queues have a priority, queue 2 receives the frame. An empty
+    /// slice stops assigning frames to the queue. By default, no priorities
+    /// are assigned.
+    ///
+    /// You may call this before you attach rings to the queue. You may call
+    /// this while the MAC is enabled.
+    pub fn set_queue_priorities(
+        &mut self,
+        queue: Queue,
+        priorities: &[u8],
+    ) -> Result<(), QueueError> {
+        if !self.gigabit {
+            return Err(QueueError::Unsupported);
+        }
+        let class = queue.class().ok_or(QueueError::DefaultQueue)?;
+        self.queues.rcmr[class] = rcmr(priorities)?;
+        if self.queues.rings[class].is_some() {
+            // Safety: the driver manages ENET_1G.
+            let enet = unsafe { as_enet_1g(&self.enet) };
+            ral::write_reg!(ral::enet_1g, enet, RCMR[class], self.queues.rcmr[class]);
+        }
+        Ok(())
+    }
+}
+
+impl Enet<Running> {
+    /// Transmit a frame on `queue`.
+    ///
+    /// Returns `None` if the queue does not have rings, if there's no space
+    /// to transmit the frame, or if the MAC is stopped for the link.
+    /// Otherwise, consume the token to send the frame. Frames on queues 1
+    /// and 2 are not timestamped.
+    pub fn transmit_on(&mut self, queue: Queue) -> Option<impl smoltcp::phy::TxToken + '_> {
+        if !self.mac_enabled() {
+            return None;
+        }
+        match queue.class() {
+            None => self
+                .tx_ring
+                .next_token(TxReady::new(&self.enet, &mut self.tx_timestamps)),
+            Some(class) => {
+                let rings = self.queues.rings[class].as_mut()?;
+                rings.tx.next_token(TxReady::on(&self.enet, queue))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueueError, rcmr};
+
+    #[test]
+    fn classification() {
+        assert_eq!(rcmr(&[]), Ok(0));
+        assert_eq!(rcmr(&[5]), Ok(0x1_5555));
+        assert_eq!(rcmr(&[6, 7]), Ok(0x1_6676));
+        assert_eq!(rcmr(&[0, 1, 2, 3]), Ok(0x1_3210));
+        assert_eq!(rcmr(&[0, 1, 2, 3, 4]), Err(QueueError::Priorities));
+        assert_eq!(rcmr(&[8]), Err(QueueError::Priorities));
+    }
+}
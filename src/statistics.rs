@@ -0,0 +1,178 @@
+//! MAC statistics from the MIB / RMON counters.
+
+use crate::ral;
+
+macro_rules! statistics {
+    ($(
+        $(#[$doc:meta])*
+        $field:ident: $ty:ty = $reg:ident,
+    )*) => {
+        /// A snapshot of the MAC's statistic counters.
+        ///
+        /// The snapshot includes every RMON and IEEE counter, except for
+        /// counters that the reference manual marks as reserved.
+        ///
+        /// Packet counters are 16 bits wide, and octet counters are 32 bits
+        /// wide. The hardware counters wrap. Use [`delta`](Self::delta) to
+        /// compute the change between two snapshots, and read snapshots often
+        /// enough that a counter doesn't wrap more than once.
+        ///
+        /// The hardware only updates the counters when MIB statistics are
+        /// enabled. See [`Enet::enable_mib`](crate::Enet::enable_mib).
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct MacStatistics {
+            $(
+                $(#[$doc])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl MacStatistics {
+            pub(crate) fn read(enet: &ral::enet::RegisterBlock) -> Self {
+                Self {
+                    $($field: ral::read_reg!(ral::enet, enet, $reg) as $ty,)*
+                }
+            }
+
+            /// Returns the change in every counter since the `earlier` snapshot.
+            ///
+            /// This accounts for counters that wrapped once since the
+            /// `earlier` snapshot.
+            pub fn delta(&self, earlier: &Self) -> Self {
+                Self {
+                    $($field: self.$field.wrapping_sub(earlier.$field),)*
+                }
+            }
+        }
+    };
+}
+
+statistics! {
+    /// Transmitted packets.
+    rmon_t_packets: u16 = RMON_T_PACKETS,
+    /// Transmitted broadcast packets.
+    rmon_t_bc_pkt: u16 = RMON_T_BC_PKT,
+    /// Transmitted multicast packets.
+    rmon_t_mc_pkt: u16 = RMON_T_MC_PKT,
+    /// Transmitted packets with a CRC or alignment error.
+    rmon_t_crc_align: u16 = RMON_T_CRC_ALIGN,
+    /// Transmitted packets less than 64 bytes with a good CRC.
+    rmon_t_undersize: u16 = RMON_T_UNDERSIZE,
+    /// Transmitted packets greater than the maximum frame length with a good CRC.
+    rmon_t_oversize: u16 = RMON_T_OVERSIZE,
+    /// Transmitted packets less than 64 bytes with a bad CRC.
+    rmon_t_frag: u16 = RMON_T_FRAG,
+    /// Transmitted packets greater than the maximum frame length with a bad CRC.
+    rmon_t_jab: u16 = RMON_T_JAB,
+    /// Transmit collisions.
+    rmon_t_col: u16 = RMON_T_COL,
+    /// Transmitted 64-byte packets.
+    rmon_t_p64: u16 = RMON_T_P64,
+    /// Transmitted 65- to 127-byte packets.
+    rmon_t_p65to127: u16 = RMON_T_P65TO127,
+    /// Transmitted 128- to 255-byte packets.
+    rmon_t_p128to255: u16 = RMON_T_P128TO255,
+    /// Transmitted 256- to 511-byte packets.
+    rmon_t_p256to511: u16 = RMON_T_P256TO511,
+    /// Transmitted 512- to 1023-byte packets.
+    rmon_t_p512to1023: u16 = RMON_T_P512TO1023,
+    /// Transmitted 1024- to 2047-byte packets.
+    rmon_t_p1024to2047: u16 = RMON_T_P1024TO2047,
+    /// Transmitted packets of 2048 bytes or more.
+    rmon_t_p_gte2048: u16 = RMON_T_P_GTE2048,
+    /// Transmitted octets.
+    rmon_t_octets: u32 = RMON_T_OCTETS,
+    /// Frames transmitted without error.
+    ieee_t_frame_ok: u16 = IEEE_T_FRAME_OK,
+    /// Frames transmitted with one collision.
+    ieee_t_1col: u16 = IEEE_T_1COL,
+    /// Frames transmitted with multiple collisions.
+    ieee_t_mcol: u16 = IEEE_T_MCOL,
+    /// Frames transmitted after a deferral delay.
+    ieee_t_def: u16 = IEEE_T_DEF,
+    /// Frames transmitted with a late collision.
+    ieee_t_lcol: u16 = IEEE_T_LCOL,
+    /// Frames not transmitted due to excessive collisions.
+    ieee_t_excol: u16 = IEEE_T_EXCOL,
+    /// Frames transmitted with a transmit FIFO underrun.
+    ieee_t_macerr: u16 = IEEE_T_MACERR,
+    /// Frames transmitted with a carrier sense error.
+    ieee_t_cserr: u16 = IEEE_T_CSERR,
+    /// Flow control pause frames transmitted.
+    ieee_t_fdxfc: u16 = IEEE_T_FDXFC,
+    /// Octets of frames transmitted without error.
+    ieee_t_octets_ok: u32 = IEEE_T_OCTETS_OK,
+    /// Received packets.
+    rmon_r_packets: u16 = RMON_R_PACKETS,
+    /// Received broadcast packets.
+    rmon_r_bc_pkt: u16 = RMON_R_BC_PKT,
+    /// Received multicast packets.
+    rmon_r_mc_pkt: u16 = RMON_R_MC_PKT,
+    /// Received packets with a CRC or alignment error.
+    rmon_r_crc_align: u16 = RMON_R_CRC_ALIGN,
+    /// Received packets less than 64 bytes with a good CRC.
+    rmon_r_undersize: u16 = RMON_R_UNDERSIZE,
+    /// Received packets greater than the maximum frame length with a good CRC.
+    rmon_r_oversize: u16 = RMON_R_OVERSIZE,
+    /// Received packets less than 64 bytes with a bad CRC.
+    rmon_r_frag: u16 = RMON_R_FRAG,
+    /// Received packets greater than the maximum frame length with a bad CRC.
+    rmon_r_jab: u16 = RMON_R_JAB,
+    /// Received 64-byte packets.
+    rmon_r_p64: u16 = RMON_R_P64,
+    /// Received 65- to 127-byte packets.
+    rmon_r_p65to127: u16 = RMON_R_P65TO127,
+    /// Received 128- to 255-byte packets.
+    rmon_r_p128to255: u16 = RMON_R_P128TO255,
+    /// Received 256- to 511-byte packets.
+    rmon_r_p256to511: u16 = RMON_R_P256TO511,
+    /// Received 512- to 1023-byte packets.
+    rmon_r_p512to1023: u16 = RMON_R_P512TO1023,
+    /// Received 1024- to 2047-byte packets.
+    rmon_r_p1024to2047: u16 = RMON_R_P1024TO2047,
+    /// Received packets of 2048 bytes or more.
+    rmon_r_p_gte2048: u16 = RMON_R_P_GTE2048,
+    /// Received octets.
+    rmon_r_octets: u32 = RMON_R_OCTETS,
+    /// Frames not counted correctly.
+    ieee_r_drop: u16 = IEEE_R_DROP,
+    /// Frames received without error.
+    ieee_r_frame_ok: u16 = IEEE_R_FRAME_OK,
+    /// Frames received with a CRC error.
+    ieee_r_crc: u16 = IEEE_R_CRC,
+    /// Frames received with an alignment error.
+    ieee_r_align: u16 = IEEE_R_ALIGN,
+    /// Receive FIFO overflows.
+    ieee_r_macerr: u16 = IEEE_R_MACERR,
+    /// Flow control pause frames received.
+    ieee_r_fdxfc: u16 = IEEE_R_FDXFC,
+    /// Octets of frames received without error.
+    ieee_r_octets_ok: u32 = IEEE_R_OCTETS_OK,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MacStatistics;
+
+    #[test]
+    fn delta_accounts_for_wrap() {
+        let earlier = MacStatistics {
+            rmon_t_packets: u16::MAX - 1,
+            rmon_t_octets: u32::MAX - 99,
+            ieee_r_crc: 3,
+            ..Default::default()
+        };
+        let later = MacStatistics {
+            rmon_t_packets: 3,
+            rmon_t_octets: 100,
+            ieee_r_crc: 3,
+            ..Default::default()
+        };
+
+        let delta = later.delta(&earlier);
+        assert_eq!(delta.rmon_t_packets, 5);
+        assert_eq!(delta.rmon_t_octets, 200);
+        assert_eq!(delta.ieee_r_crc, 0);
+    }
+}